@@ -253,6 +253,7 @@ fn testnet_genesis(
 		),
 		treasury: Default::default(),
 		transaction_storage: Default::default(),
+		token_fungible: Default::default(),
 		wrap_currency: Default::default(),
 		martketplace: MartketplaceConfig { admin_key: marketplace_admin },
 	}
@@ -40,7 +40,9 @@ use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_runtime::traits::BlakeTwo256;
 use std::{collections::BTreeMap, sync::Arc};
-use web3games_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index};
+use web3games_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, TokenAssetId,
+};
 
 /// Full client dependencies.
 pub struct FullDeps<C, P, A: ChainApi> {
@@ -130,6 +132,12 @@ where
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
 	C::Api: pallet_exchange_rpc::ExchangeRuntimeApi<Block, AccountId>,
+	C::Api: pallet_token_fungible_rpc::TokenFungibleRuntimeApi<
+		Block,
+		TokenAssetId,
+		AccountId,
+		BlockNumber,
+	>,
 	P: TransactionPool<Block = Block> + 'static,
 	A: ChainApi<Block = Block> + 'static,
 {
@@ -139,6 +147,7 @@ where
 	};
 	use pallet_contracts_rpc::{Contracts, ContractsApiServer};
 	use pallet_exchange_rpc::{ExchangeRpc, ExchangeRpcApiServer};
+	use pallet_token_fungible_rpc::{FungibleApiServer, FungibleRpc};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -165,6 +174,7 @@ where
 	io.merge(TransactionPayment::new(Arc::clone(&client)).into_rpc())?;
 	io.merge(Contracts::new(Arc::clone(&client)).into_rpc())?;
 	io.merge(ExchangeRpc::new(Arc::clone(&client)).into_rpc())?;
+	io.merge(FungibleRpc::new(Arc::clone(&client)).into_rpc())?;
 
 	let mut signers = Vec::new();
 	if enable_dev_signer {
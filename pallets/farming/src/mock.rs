@@ -96,9 +96,19 @@ impl pallet_balances::Config for Test {
 parameter_types! {
 	pub const TokenFungiblePalletId: PalletId = PalletId(*b"w3g/tfpi");
 	pub const StringLimit: u32 = 50;
+	pub const MetadataOverflow: pallet_token_fungible::MetadataOverflowPolicy =
+		pallet_token_fungible::MetadataOverflowPolicy::Reject;
 	pub const CreateTokenDeposit: Balance = 500 * MILLICENTS;
 }
 
+pub struct MockRandomness;
+
+impl frame_support::traits::Randomness<H256, u64> for MockRandomness {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		(H256::zero(), 0)
+	}
+}
+
 impl pallet_token_fungible::Config for Test {
 	type Event = Event;
 	type PalletId = TokenFungiblePalletId;
@@ -106,6 +116,26 @@ impl pallet_token_fungible::Config for Test {
 	type StringLimit = StringLimit;
 	type CreateTokenDeposit = CreateTokenDeposit;
 	type Currency = Balances;
+	type MaxBatchTransfers = frame_support::traits::ConstU32<10>;
+	type OnMetadataOverflow = MetadataOverflow;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type InactivityThreshold = ConstU64<100>;
+	type NormalizeSymbols = frame_support::traits::ConstBool<false>;
+	type DefaultDecimals = frame_support::traits::ConstU8<18>;
+	type EmitBalanceChanged = frame_support::traits::ConstBool<false>;
+	type OnBalanceChanged = ();
+	type MintFee = frame_support::traits::ConstU128<0>;
+	type Randomness = MockRandomness;
+	type MaxLotteryHolders = frame_support::traits::ConstU32<100>;
+	type MaxBatchMints = frame_support::traits::ConstU32<10>;
+	type UnsafeRecipients = frame_support::traits::Nothing;
+	type MinNameLength = frame_support::traits::ConstU32<1>;
+	type MinSymbolLength = frame_support::traits::ConstU32<2>;
+	type MaxApprovalsPerOwner = ();
+	type MaxEmissions = frame_support::traits::ConstU32<10>;
+	type BridgeOrigin = frame_system::EnsureRoot<u64>;
+	type MaxFeeSplits = frame_support::traits::ConstU32<4>;
+	type FeeRounding = ();
 	type WeightInfo = ();
 }
 
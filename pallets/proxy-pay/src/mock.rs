@@ -103,6 +103,8 @@ impl pallet_randomness_collective_flip::Config for Test {}
 parameter_types! {
 	pub const TokenFungiblePalletId: PalletId = PalletId(*b"w3g/tfpi");
 	pub const StringLimit: u32 = 50;
+	pub const MetadataOverflow: pallet_token_fungible::MetadataOverflowPolicy =
+		pallet_token_fungible::MetadataOverflowPolicy::Reject;
 	pub const CreateTokenDeposit: Balance = 500 * MILLICENTS;
 }
 
@@ -113,6 +115,26 @@ impl pallet_token_fungible::Config for Test {
 	type StringLimit = StringLimit;
 	type CreateTokenDeposit = CreateTokenDeposit;
 	type Currency = Balances;
+	type MaxBatchTransfers = frame_support::traits::ConstU32<10>;
+	type OnMetadataOverflow = MetadataOverflow;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type InactivityThreshold = ConstU64<100>;
+	type NormalizeSymbols = frame_support::traits::ConstBool<false>;
+	type DefaultDecimals = frame_support::traits::ConstU8<18>;
+	type EmitBalanceChanged = frame_support::traits::ConstBool<false>;
+	type OnBalanceChanged = ();
+	type MintFee = frame_support::traits::ConstU128<0>;
+	type Randomness = Randomness;
+	type MaxLotteryHolders = frame_support::traits::ConstU32<100>;
+	type MaxBatchMints = frame_support::traits::ConstU32<10>;
+	type UnsafeRecipients = frame_support::traits::Nothing;
+	type MinNameLength = frame_support::traits::ConstU32<1>;
+	type MinSymbolLength = frame_support::traits::ConstU32<2>;
+	type MaxApprovalsPerOwner = ();
+	type MaxEmissions = frame_support::traits::ConstU32<10>;
+	type BridgeOrigin = frame_system::EnsureRoot<u64>;
+	type MaxFeeSplits = frame_support::traits::ConstU32<4>;
+	type FeeRounding = ();
 	type WeightInfo = ();
 }
 
@@ -0,0 +1,91 @@
+// This file is part of Web3Games.
+
+// Copyright (C) 2021-2022 Web3Games https://web3games.org
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_token_fungible::TokenInfo;
+use primitives::Balance;
+use sp_api::decl_runtime_apis;
+use sp_core::H160;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+decl_runtime_apis! {
+	pub trait TokenFungibleRuntimeApi<FungibleTokenId, AccountId, BlockNumber> where
+		FungibleTokenId: Codec,
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		fn all_tokens(
+			start: Option<FungibleTokenId>,
+			limit: u32,
+		) -> Vec<(FungibleTokenId, TokenInfo<AccountId, BlockNumber>)>;
+
+		fn can_transfer_from(
+			id: FungibleTokenId,
+			sender: AccountId,
+			spender: AccountId,
+			recipient: AccountId,
+			amount: Balance,
+		) -> Result<(), DispatchError>;
+
+		/// The balance as a UTF-8 decimal string, scaled by the token's decimals.
+		fn formatted_balance(id: FungibleTokenId, who: AccountId) -> Vec<u8>;
+
+		/// What `who` can transfer of its balance.
+		fn spendable_balance(id: FungibleTokenId, who: AccountId) -> Balance;
+
+		/// The allowances `owner` granted to each of `spenders`, in order.
+		fn allowance_batch(
+			id: FungibleTokenId,
+			owner: AccountId,
+			spenders: Vec<AccountId>,
+		) -> Vec<Balance>;
+
+		/// The number of spenders `owner` has a non-zero allowance for.
+		fn approvals_count(id: FungibleTokenId, owner: AccountId) -> u32;
+
+		/// The sum of the allowances `owner` granted, `Balance::MAX` if any is unlimited.
+		fn total_approved(id: FungibleTokenId, owner: AccountId) -> Balance;
+
+		/// Whether `create_token_named` would accept the metadata, without creating anything.
+		fn validate_create(
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> Result<(), DispatchError>;
+
+		/// The ids the next `count` creations without an explicit id would receive.
+		fn peek_token_ids(count: u32) -> Vec<FungibleTokenId>;
+
+		fn balance_of(id: FungibleTokenId, who: AccountId) -> Balance;
+
+		fn allowance(id: FungibleTokenId, owner: AccountId, spender: AccountId) -> Balance;
+
+		fn total_supply(id: FungibleTokenId) -> Balance;
+
+		fn token_info(id: FungibleTokenId) -> Option<TokenInfo<AccountId, BlockNumber>>;
+
+		/// The address of the ERC20 precompile serving the token.
+		fn evm_address_of(id: FungibleTokenId) -> H160;
+
+		/// The token served by the ERC20 precompile at `address`, if it is one.
+		fn token_id_from_evm_address(address: H160) -> Option<FungibleTokenId>;
+	}
+}
@@ -0,0 +1,164 @@
+// This file is part of Web3Games.
+
+// Copyright (C) 2021-2022 Web3Games https://web3games.org
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+pub use pallet_token_fungible_rpc_runtime_api::{TokenFungibleRuntimeApi, TokenInfo};
+use primitives::Balance;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{marker::PhantomData, sync::Arc};
+
+pub struct FungibleRpc<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Client, Block> FungibleRpc<Client, Block> {
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _marker: PhantomData }
+	}
+}
+
+#[rpc(client, server)]
+pub trait FungibleApi<BlockHash, FungibleTokenId, AccountId, BlockNumber> {
+	#[method(name = "fungible_balanceOf")]
+	fn balance_of(
+		&self,
+		id: FungibleTokenId,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Balance>;
+
+	#[method(name = "fungible_allowance")]
+	fn allowance(
+		&self,
+		id: FungibleTokenId,
+		owner: AccountId,
+		spender: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Balance>;
+
+	#[method(name = "fungible_totalSupply")]
+	fn total_supply(&self, id: FungibleTokenId, at: Option<BlockHash>) -> RpcResult<Balance>;
+
+	#[method(name = "fungible_tokenInfo")]
+	fn token_info(
+		&self,
+		id: FungibleTokenId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<TokenInfo<AccountId, BlockNumber>>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+#[async_trait]
+impl<C, Block, FungibleTokenId, AccountId, BlockNumber>
+	FungibleApiServer<<Block as BlockT>::Hash, FungibleTokenId, AccountId, BlockNumber>
+	for FungibleRpc<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TokenFungibleRuntimeApi<Block, FungibleTokenId, AccountId, BlockNumber>,
+	FungibleTokenId: Codec + DeserializeOwned + Send + Sync + 'static,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+{
+	fn balance_of(
+		&self,
+		id: FungibleTokenId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.balance_of(&at, id, who).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn allowance(
+		&self,
+		id: FungibleTokenId,
+		owner: AccountId,
+		spender: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.allowance(&at, id, owner, spender).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn total_supply(
+		&self,
+		id: FungibleTokenId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.total_supply(&at, id).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn token_info(
+		&self,
+		id: FungibleTokenId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<TokenInfo<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.token_info(&at, id).map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Display) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"error in token fungible pallet",
+		Some(err.to_string()),
+	))
+	.into()
+}
@@ -9,16 +9,21 @@ use crate::Pallet as TokenFungible;
 use codec::alloc::string::ToString;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
 use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
 
 const SEED: u32 = 0;
 
 benchmarks! {
 	create_token {
+		let n in T::MinNameLength::get() .. T::StringLimit::get();
+		let s in T::MinSymbolLength::get() .. T::StringLimit::get();
 		let alice: T::AccountId = account("alice", 0, SEED);
-	}: _(RawOrigin::Signed(alice), 1u32.into(), "TestToken".to_string().into(), "TK".to_string().into(), 18)
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
+	}: _(RawOrigin::Signed(alice), 1u32.into(), vec![b'a'; n as usize], vec![b'A'; s as usize], 18)
 
 	mint {
 		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
 		let bob: T::AccountId = account("bob", 0, SEED);
 
 		// let recipient: T::AccountId = account("recipient", 0, SEED);
@@ -27,6 +32,7 @@ benchmarks! {
 
 	approve {
 		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
 		let bob: T::AccountId = account("bob", 0, SEED);
 
 		let _ = TokenFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), "TestToken".to_string().into(), "TK".to_string().into(), 18);
@@ -35,6 +41,7 @@ benchmarks! {
 
 	burn {
 		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
 		let bob: T::AccountId = account("bob", 0, SEED);
 
 		let _ = TokenFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), "TestToken".to_string().into(), "TK".to_string().into(), 18);
@@ -43,6 +50,7 @@ benchmarks! {
 
 	transfer {
 		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
 		let bob: T::AccountId = account("bob", 0, SEED);
 
 		let _ = TokenFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), "TestToken".to_string().into(), "TK".to_string().into(), 18);
@@ -51,6 +59,7 @@ benchmarks! {
 
 	transfer_from {
 		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
 		let bob: T::AccountId = account("bob", 0, SEED);
 		let charlie: T::AccountId = account("charlie", 0, SEED);
 
@@ -58,6 +67,14 @@ benchmarks! {
 		let _ = TokenFungible::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), alice.clone(), 100_000_000_000_000u128);
 		let _ = TokenFungible::<T>::approve(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), charlie.clone(), 100_000_000_000_000u128);
 	}: _(RawOrigin::Signed(charlie), 1u32.into(), alice, bob, 100_000_000_000u128)
+
+	// Every owner setter that only updates the token costs about the same, measured on one.
+	set_config {
+		let alice: T::AccountId = account("alice", 0, SEED);
+		T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 2u32.into());
+
+		let _ = TokenFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), "TestToken".to_string().into(), "TK".to_string().into(), 18);
+	}: set_max_wallet(RawOrigin::Signed(alice), 1u32.into(), Some(100_000_000_000u128))
 }
 
 impl_benchmark_test_suite!(TokenFungible, crate::mock::new_test_ext(), crate::mock::Test,);
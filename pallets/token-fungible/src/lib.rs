@@ -20,17 +20,28 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	dispatch::DispatchResult,
+	dispatch::{DispatchError, DispatchResult},
 	ensure,
-	traits::{Currency, Get, ReservableCurrency},
+	storage::with_storage_layer,
+	traits::{
+		tokens::{fungibles, DepositConsequence, WithdrawConsequence},
+		Contains, Currency, ExistenceRequirement, Get, Randomness, ReservableCurrency,
+	},
+	weights::{Weight, WithPostDispatchInfo},
 	BoundedVec, PalletId,
 };
 use pallet_support::FungibleMetadata;
 use primitives::Balance;
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::{H256, U256};
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, TrailingZeroInput},
-	RuntimeDebug,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, One, SaturatedConversion, Saturating,
+		TrailingZeroInput, Zero,
+	},
+	Perbill, RuntimeDebug,
 };
 use sp_std::prelude::*;
 
@@ -39,6 +50,7 @@ mod benchmarking;
 
 pub use pallet::*;
 
+pub mod migrations;
 pub mod weights;
 pub use weights::WeightInfo;
 
@@ -52,22 +64,206 @@ type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
-pub struct Token<AccountId, BoundedString> {
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Token<AccountId, BoundedString, BlockNumber> {
 	owner: AccountId,
 	name: BoundedString,
 	symbol: BoundedString,
 	decimals: u8,
 	total_supply: Balance,
+	created_at: BlockNumber,
+	last_activity: BlockNumber,
+	max_allowance: Option<Balance>,
+	transfer_policy: TransferPolicy,
+	max_holders: Option<u32>,
+	transfer_cooldown: Option<BlockNumber>,
+	require_memo: bool,
+	max_supply: Option<Balance>,
+	whole_units_only: bool,
+	emit_transfer_events: bool,
+	max_tx_amount: Option<Balance>,
+	max_wallet: Option<Balance>,
 }
 
+impl<AccountId, BoundedString, BlockNumber: Copy> Token<AccountId, BoundedString, BlockNumber> {
+	/// A token without supply created by `owner` at block `now`, with every limit lifted and
+	/// every flag at its default. `None` if `decimals` exceeds `MAX_DECIMALS`.
+	pub fn new(
+		owner: AccountId,
+		name: BoundedString,
+		symbol: BoundedString,
+		decimals: u8,
+		now: BlockNumber,
+	) -> Option<Self> {
+		if decimals > MAX_DECIMALS {
+			return None
+		}
+		Some(Token {
+			owner,
+			name,
+			symbol,
+			decimals,
+			total_supply: Zero::zero(),
+			created_at: now,
+			last_activity: now,
+			max_allowance: None,
+			transfer_policy: TransferPolicy::Open,
+			max_holders: None,
+			transfer_cooldown: None,
+			require_memo: false,
+			max_supply: None,
+			whole_units_only: false,
+			emit_transfer_events: true,
+			max_tx_amount: None,
+			max_wallet: None,
+		})
+	}
+
+	pub fn owner(&self) -> &AccountId {
+		&self.owner
+	}
+
+	pub fn name(&self) -> &BoundedString {
+		&self.name
+	}
+
+	pub fn symbol(&self) -> &BoundedString {
+		&self.symbol
+	}
+
+	pub fn decimals(&self) -> u8 {
+		self.decimals
+	}
+
+	pub fn total_supply(&self) -> Balance {
+		self.total_supply
+	}
+
+	pub fn max_supply(&self) -> Option<Balance> {
+		self.max_supply
+	}
+}
+
+/// Which accounts may send and receive a token.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TransferPolicy {
+	/// Anyone.
+	Open,
+	/// Only listed accounts, on both sides of a transfer.
+	Allowlist,
+	/// Anyone but listed accounts.
+	Blocklist,
+}
+
+impl Default for TransferPolicy {
+	fn default() -> Self {
+		TransferPolicy::Open
+	}
+}
+
+/// A resting offer to sell `sell_amount` of `sell_id` for `buy_amount` of `buy_id`, which
+/// also fixes the price of partial fills. `remaining` of the sell side is still in escrow.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct LimitOrder<AccountId, FungibleTokenId> {
+	pub maker: AccountId,
+	pub sell_id: FungibleTokenId,
+	pub sell_amount: Balance,
+	pub buy_id: FungibleTokenId,
+	pub buy_amount: Balance,
+	pub remaining: Balance,
+}
+
+/// `total` of token `id` paid from `sender` to `recipient` linearly between `start` and
+/// `end`. `withdrawn` of it was already paid out, the rest is still in escrow.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct Stream<AccountId, FungibleTokenId, BlockNumber> {
+	pub id: FungibleTokenId,
+	pub sender: AccountId,
+	pub recipient: AccountId,
+	pub total: Balance,
+	pub withdrawn: Balance,
+	pub start: BlockNumber,
+	pub end: BlockNumber,
+}
+
+/// What to do with a name or symbol longer than `StringLimit`.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum MetadataOverflowPolicy {
+	/// Fail with `BadMetadata`.
+	Reject,
+	/// Keep the first `StringLimit` bytes.
+	Truncate,
+}
+
+/// How fees and split shares that aren't a whole number of base units are rounded.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum RoundingMode {
+	/// Down, in favour of the payer.
+	Floor,
+	/// Up, in favour of the payee.
+	Ceil,
+	/// To the nearest base unit.
+	Nearest,
+}
+
+impl Default for RoundingMode {
+	fn default() -> Self {
+		RoundingMode::Floor
+	}
+}
+
+/// Told about every balance change of a fungible token once it is written. Implementations may
+/// call back into the pallet, so nothing the change is part of may hold a stale copy of the
+/// token meanwhile.
+pub trait OnBalanceChanged<FungibleTokenId, AccountId> {
+	fn on_balance_changed(id: FungibleTokenId, who: &AccountId, before: Balance, after: Balance);
+}
+
+impl<FungibleTokenId, AccountId> OnBalanceChanged<FungibleTokenId, AccountId> for () {
+	fn on_balance_changed(_: FungibleTokenId, _: &AccountId, _: Balance, _: Balance) {}
+}
+
+/// Most decimals a token can have, so that one whole unit still fits in a `Balance`.
+pub const MAX_DECIMALS: u8 = 38;
+
+/// Basis points making up the whole of an amount.
+pub const MAX_BPS: u16 = 10_000;
+
+/// Scale of `MagnifiedPerShare`, so reflections smaller than the eligible supply still
+/// accumulate.
+const REFLECTION_MAGNITUDE: u128 = 1 << 64;
+
+/// Most spenders `allowance_batch` answers for in one query.
+pub const MAX_ALLOWANCE_BATCH: u32 = 100;
+
+/// Most ids `peek_token_ids` returns in one query.
+pub const MAX_PEEK_TOKEN_IDS: u32 = 100;
+
+/// Most per-account entries `reap_inactive` removes in one call.
+pub const MAX_REAP_REMOVALS: u32 = 500;
+
+/// Most allowance entries of a token `total_approved` walks in one query.
+pub const MAX_TOTAL_APPROVED_SCAN: u32 = 10_000;
+
+/// Randomness subject of `lottery_distribute` draws.
+const LOTTERY_SUBJECT: &[u8] = b"w3g/lottery";
+
+/// Token metadata as returned to off-chain consumers.
+pub type TokenInfo<AccountId, BlockNumber> = Token<AccountId, Vec<u8>, BlockNumber>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(16);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -88,12 +284,96 @@ pub mod pallet {
 		#[pallet::constant]
 		type StringLimit: Get<u32>;
 
+		/// The minimum length of a token name.
+		#[pallet::constant]
+		type MinNameLength: Get<u32>;
+
+		/// The minimum length of a token symbol.
+		#[pallet::constant]
+		type MinSymbolLength: Get<u32>;
+
+		/// How over-long names and symbols are handled.
+		#[pallet::constant]
+		type OnMetadataOverflow: Get<MetadataOverflowPolicy>;
+
+		/// How transfer and reflection fees, fee split shares and `transfer_split` shares are
+		/// rounded to base units.
+		#[pallet::constant]
+		type FeeRounding: Get<RoundingMode>;
+
+		/// Whether registered symbols are compared trimmed and uppercased, so that "usd"
+		/// collides with "USD". Tokens always keep the symbol as given.
+		#[pallet::constant]
+		type NormalizeSymbols: Get<bool>;
+
+		/// Whether every balance change also deposits `BalanceChanged` with the balance before
+		/// and after, for indexers that track absolute balances.
+		#[pallet::constant]
+		type EmitBalanceChanged: Get<bool>;
+
+		/// Called after every balance change.
+		type OnBalanceChanged: OnBalanceChanged<Self::FungibleTokenId, Self::AccountId>;
+
 		/// The minimum balance to create token
 		#[pallet::constant]
 		type CreateTokenDeposit: Get<BalanceOf<Self>>;
 
+		/// The fee in native currency charged to the minter for every account credited by
+		/// `mint`, `mint_and_approve` or `mint_batch`, paid to `treasury_account_id`. Zero
+		/// disables it.
+		#[pallet::constant]
+		type MintFee: Get<BalanceOf<Self>>;
+
+		/// Decimals of tokens created through `create_token_default`.
+		#[pallet::constant]
+		type DefaultDecimals: Get<u8>;
+
+		/// The maximum number of recipients in a single `transfer_batch`.
+		#[pallet::constant]
+		type MaxBatchTransfers: Get<u32>;
+
+		/// Accounts `safe_transfer` refuses to send to, on top of the zero account and the
+		/// pallet's own accounts.
+		type UnsafeRecipients: Contains<Self::AccountId>;
+
+		/// The maximum number of accounts credited by a single `mint_batch`.
+		#[pallet::constant]
+		type MaxBatchMints: Get<u32>;
+
+		/// The maximum number of spenders an owner may have an allowance for, per token.
+		/// `None` leaves approvals unbounded.
+		#[pallet::constant]
+		type MaxApprovalsPerOwner: Get<Option<u32>>;
+
+		/// The maximum number of tokens with an emission schedule, all of which are minted
+		/// for in `on_initialize`.
+		#[pallet::constant]
+		type MaxEmissions: Get<u32>;
+
+		/// The maximum number of recipients a token's transfer fee can be split between.
+		#[pallet::constant]
+		type MaxFeeSplits: Get<u32>;
+
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
 
+		/// Source of `lottery_distribute` draws.
+		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+		/// The maximum number of holders a `lottery_distribute` draws from.
+		#[pallet::constant]
+		type MaxLotteryHolders: Get<u32>;
+
+		/// The origin allowed to reap inactive tokens.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin allowed to mint tokens locked on another chain.
+		type BridgeOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of blocks without a transfer, mint or burn after which an empty token
+		/// may be reaped.
+		#[pallet::constant]
+		type InactivityThreshold: Get<Self::BlockNumber>;
+
 		/// runtime weights.
 		type WeightInfo: WeightInfo;
 	}
@@ -103,7 +383,7 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		T::FungibleTokenId,
-		Token<T::AccountId, BoundedVec<u8, T::StringLimit>>,
+		Token<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
 	>;
 
 	#[pallet::storage]
@@ -131,210 +411,2851 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
-		TokenCreated(T::FungibleTokenId, T::AccountId, Vec<u8>, Vec<u8>, u8),
-		Mint(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
-		Transfer(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
-		Approval(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
-	}
+	/// Allowances cleared by the first `transfer_from` spending them, however much is left.
+	#[pallet::storage]
+	#[pallet::getter(fn is_single_use_approval)]
+	pub(super) type SingleUseApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		// (owner, spender)
+		(T::AccountId, T::AccountId),
+		bool,
+		ValueQuery,
+	>;
 
-	#[pallet::error]
-	pub enum Error<T> {
-		Unknown,
-		NoAvailableTokenId,
-		NumOverflow,
-		NoPermission,
-		NotOwner,
-		InvalidId,
-		AmountExceedAllowance,
-		BadMetadata,
-		InsufficientAuthorizedTokens,
-		InsufficientTokens,
-		ConfuseBehavior,
-		ApproveToCurrentOwner,
-	}
+	/// Tokens created through `create_token_idempotent`, by creator and symbol.
+	#[pallet::storage]
+	#[pallet::getter(fn idempotent_token_id)]
+	pub(super) type IdempotentTokens<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		BoundedVec<u8, T::StringLimit>,
+		T::FungibleTokenId,
+	>;
 
-	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	/// The next id tried when a token is created without an explicit id.
+	#[pallet::storage]
+	pub(super) type NextTokenId<T: Config> = StorageValue<_, T::FungibleTokenId, ValueQuery>;
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		#[pallet::weight(T::WeightInfo::create_token())]
-		pub fn create_token(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			name: Vec<u8>,
-			symbol: Vec<u8>,
-			decimals: u8,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			ensure!(!Self::exists(id.clone()), Error::<T>::InvalidId);
-			Self::do_create_token(&who, id, name, symbol, decimals)
-		}
+	/// Symbols registered through `create_token_named`, mapped to their token id.
+	#[pallet::storage]
+	pub(super) type SymbolToId<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::StringLimit>, T::FungibleTokenId>;
 
-		#[pallet::weight(T::WeightInfo::approve())]
-		pub fn approve(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			spender: T::AccountId,
-			amount: Balance,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			Self::do_approve(id, &who, &spender, amount)
-		}
+	/// Fee charged on transfers, in basis points of the transferred amount, paid to the
+	/// token owner.
+	#[pallet::storage]
+	#[pallet::getter(fn transfer_fee_bps)]
+	pub(super) type TransferFeeBps<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, u16, ValueQuery>;
 
-		#[pallet::weight(T::WeightInfo::transfer())]
-		pub fn transfer(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			recipient: T::AccountId,
-			amount: Balance,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			Self::do_transfer(id, &who, &recipient, amount)
-		}
+	/// Recipients the transfer fee is split between instead of going to the token owner,
+	/// each with its share in basis points of the transferred amount. The zero account's share
+	/// is burned.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_split)]
+	pub(super) type FeeSplits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		BoundedVec<(T::AccountId, u16), T::MaxFeeSplits>,
+		ValueQuery,
+	>;
 
-		#[pallet::weight(T::WeightInfo::transfer_from())]
-		pub fn transfer_from(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			sender: T::AccountId,
-			recipient: T::AccountId,
-			amount: Balance,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			Self::do_transfer_from(id, who, sender, recipient, amount)
-		}
+	/// Nonces consumed by EIP-2612 permits, per token and owner.
+	#[pallet::storage]
+	#[pallet::getter(fn permit_nonce)]
+	pub(super) type PermitNonces<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		u64,
+		ValueQuery,
+	>;
 
-		#[pallet::weight(T::WeightInfo::mint())]
-		pub fn mint(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			account: T::AccountId,
-			amount: Balance,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			ensure!(Self::exists(id.clone()), Error::<T>::InvalidId);
-			Self::do_mint(id, &who, account, amount)
+	/// Most an operator may move out of a granter's balance, per token and
+	/// (granter, operator).
+	#[pallet::storage]
+	#[pallet::getter(fn operator_cap)]
+	pub(super) type OperatorCaps<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		(T::AccountId, T::AccountId),
+		Balance,
+		ValueQuery,
+	>;
+
+	/// What an operator has moved out of a granter's balance since its cap was set.
+	#[pallet::storage]
+	#[pallet::getter(fn operator_spent)]
+	pub(super) type OperatorSpent<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		(T::AccountId, T::AccountId),
+		Balance,
+		ValueQuery,
+	>;
+
+	/// Accounts that have held a positive balance of a token at some point. Holders from before
+	/// the flag existed are flagged for any balance entry, emptied ones included.
+	#[pallet::storage]
+	#[pallet::getter(fn has_ever_held)]
+	pub(super) type EverHeld<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Holders whose system account the pallet keeps alive with a provider reference, because it
+	/// didn't exist when they first received the token.
+	#[pallet::storage]
+	pub(super) type ProvidedAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// The number of accounts with a positive balance of a token, pallet accounts included.
+	#[pallet::storage]
+	#[pallet::getter(fn holder_count)]
+	pub(super) type HolderCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, u32, ValueQuery>;
+
+	/// Tokens whose name and symbol can no longer be changed.
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_frozen)]
+	pub(super) type MetadataFrozen<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, bool, ValueQuery>;
+
+	/// Sequence number of the last transfer, mint or burn event, across all tokens.
+	#[pallet::storage]
+	#[pallet::getter(fn event_seq)]
+	pub(super) type EventSeq<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// The creator of a token and the deposit reserved from it, returned when the token is
+	/// reaped.
+	#[pallet::storage]
+	pub(super) type TokenDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, (T::AccountId, BalanceOf<T>)>;
+
+	/// Reaped tokens whose per-account entries aren't all removed yet. Their ids can't be
+	/// reused until `reap_inactive` finishes clearing them.
+	#[pallet::storage]
+	#[pallet::getter(fn is_reaping)]
+	pub(super) type Reaping<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, bool, ValueQuery>;
+
+	/// Number of tokens created so far. Balances of different tokens don't share a unit, so
+	/// chain-wide activity is tracked as counts rather than summed amounts. Tokens from before
+	/// the count existed are counted if they weren't reaped by then.
+	#[pallet::storage]
+	#[pallet::getter(fn token_count)]
+	pub(super) type TokenCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Number of successful mints across all tokens. Mints leave no record, so those from
+	/// before the count existed aren't in it.
+	#[pallet::storage]
+	#[pallet::getter(fn mint_count)]
+	pub(super) type MintCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Number of successful burns across all tokens, like `MintCount` only since it existed.
+	#[pallet::storage]
+	#[pallet::getter(fn burn_count)]
+	pub(super) type BurnCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Everything ever minted of a token. Less `TotalBurned`, it is the token's supply.
+	#[pallet::storage]
+	#[pallet::getter(fn total_minted)]
+	pub(super) type TotalMinted<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, Balance, ValueQuery>;
+
+	/// Everything ever burned of a token.
+	#[pallet::storage]
+	#[pallet::getter(fn total_burned)]
+	pub(super) type TotalBurned<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, Balance, ValueQuery>;
+
+	/// Accounts on the allowlist or blocklist of a token, depending on its `TransferPolicy`.
+	#[pallet::storage]
+	#[pallet::getter(fn is_listed)]
+	pub(super) type ListedAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Accounts that may hold more of a token than its `max_wallet`.
+	#[pallet::storage]
+	#[pallet::getter(fn is_max_wallet_exempt)]
+	pub(super) type MaxWalletExempt<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	/// Share of each transfer, in basis points, redistributed to all holders of the token.
+	#[pallet::storage]
+	#[pallet::getter(fn reflection_fee_bps)]
+	pub(super) type ReflectionFeeBps<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, u16, ValueQuery>;
+
+	/// Reflections paid per unit held since the token was created, scaled by
+	/// `REFLECTION_MAGNITUDE`.
+	#[pallet::storage]
+	pub(super) type MagnifiedPerShare<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, U256, ValueQuery>;
+
+	/// `MagnifiedPerShare` as of the last time an account's reflections were settled.
+	#[pallet::storage]
+	pub(super) type ReflectionCheckpoints<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		U256,
+		ValueQuery,
+	>;
+
+	/// Settled reflections not yet claimed, held by the pallet account.
+	#[pallet::storage]
+	pub(super) type PendingReflections<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		Balance,
+		ValueQuery,
+	>;
+
+	/// Reflection fees paid into the pallet account and not yet claimed, settled or not.
+	#[pallet::storage]
+	#[pallet::getter(fn unclaimed_reflections)]
+	pub(super) type UnclaimedReflections<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, Balance, ValueQuery>;
+
+	/// The block of the last transfer sent by an account, kept while the token has a transfer
+	/// cooldown.
+	#[pallet::storage]
+	#[pallet::getter(fn last_transfer)]
+	pub(super) type LastTransfer<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		T::BlockNumber,
+	>;
+
+	/// The number of spenders with a non-zero allowance from an owner, per token.
+	#[pallet::storage]
+	#[pallet::getter(fn approvals_count)]
+	pub(super) type ApprovalCounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::FungibleTokenId,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// The account minted to at the start of every block and how much, per token.
+	#[pallet::storage]
+	#[pallet::getter(fn emission)]
+	pub(super) type Emissions<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::FungibleTokenId, (T::AccountId, Balance)>;
+
+	/// The external references of the bridge mints already made, per token.
+	#[pallet::storage]
+	#[pallet::getter(fn bridge_mint_processed)]
+	pub(super) type ProcessedBridgeMints<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::FungibleTokenId, Identity, H256, bool, ValueQuery>;
+
+	/// Total supply at which `SupplyThresholdReached` is deposited, per token.
+	#[pallet::storage]
+	#[pallet::getter(fn supply_threshold)]
+	pub(super) type SupplyThresholds<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, Balance>;
+
+	/// Tokens whose supply threshold was crossed since it was set.
+	#[pallet::storage]
+	#[pallet::getter(fn supply_threshold_reached)]
+	pub(super) type SupplyThresholdReached<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, bool, ValueQuery>;
+
+	/// The account that may claim a token, and after how many blocks without an owner
+	/// action, per token.
+	#[pallet::storage]
+	#[pallet::getter(fn fallback_owner)]
+	pub(super) type FallbackOwners<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, (T::AccountId, T::BlockNumber)>;
+
+	/// The block of the last action the owner of a token took on it, such as minting or
+	/// changing a setting.
+	#[pallet::storage]
+	#[pallet::getter(fn last_owner_action)]
+	pub(super) type LastOwnerAction<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::FungibleTokenId, T::BlockNumber, ValueQuery>;
+
+	/// The id of the next limit order.
+	#[pallet::storage]
+	pub(super) type NextOrderId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Open limit orders.
+	#[pallet::storage]
+	#[pallet::getter(fn order)]
+	pub(super) type Orders<T: Config> =
+		StorageMap<_, Blake2_128Concat, u64, LimitOrder<T::AccountId, T::FungibleTokenId>>;
+
+	/// The id of the next payment stream.
+	#[pallet::storage]
+	pub(super) type NextStreamId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Payment streams with funds left in escrow.
+	#[pallet::storage]
+	#[pallet::getter(fn stream)]
+	pub(super) type Streams<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Stream<T::AccountId, T::FungibleTokenId, T::BlockNumber>,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The first id handed out to tokens created without an explicit id, keeping the ids
+		/// below it free for explicit creation. Ids that are already taken are skipped, so
+		/// tokens created at genesis by other pallets can't collide with it.
+		pub next_token_id_start: T::FungibleTokenId,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { next_token_id_start: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			NextTokenId::<T>::put(self.next_token_id_start);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		TokenCreated(T::FungibleTokenId, T::AccountId, Vec<u8>, Vec<u8>, u8),
+		Mint(T::FungibleTokenId, T::AccountId, T::AccountId, Balance, u64),
+		Transfer(T::FungibleTokenId, T::AccountId, T::AccountId, Balance, u64),
+		Approval(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
+		BatchTransfer(T::FungibleTokenId, T::AccountId, u32, Balance, u64),
+		MetadataUpdated(T::FungibleTokenId, Vec<u8>, Vec<u8>),
+		MetadataFrozen(T::FungibleTokenId),
+		TokenReaped(T::FungibleTokenId),
+		OperatorSet(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
+		Rescued(T::FungibleTokenId, T::AccountId, T::AccountId, Balance),
+		ReflectionsClaimed(T::FungibleTokenId, T::AccountId, Balance),
+		/// The balance of an account changed from the first amount to the second. Only
+		/// deposited when `EmitBalanceChanged` is set.
+		BalanceChanged(T::FungibleTokenId, T::AccountId, Balance, Balance),
+		/// The creation deposit for a token was reserved from its creator.
+		DepositReserved(T::FungibleTokenId, T::AccountId, BalanceOf<T>),
+		/// The creation deposit for a token was returned to its creator.
+		DepositReturned(T::FungibleTokenId, T::AccountId, BalanceOf<T>),
+		/// A limit order was placed by the account, selling the first token and amount for
+		/// the second token and amount.
+		OrderPlaced(u64, T::AccountId, T::FungibleTokenId, Balance, T::FungibleTokenId, Balance),
+		/// A limit order was filled by the account, receiving the first amount of the sell
+		/// token and paying the second amount of the buy token.
+		OrderFilled(u64, T::AccountId, Balance, Balance),
+		/// A limit order was cancelled and the unfilled amount returned to its maker.
+		OrderCancelled(u64, Balance),
+		/// A mint took the total supply of a token to or past its supply threshold.
+		SupplyThresholdReached(T::FungibleTokenId, Balance),
+		/// A lottery paid a share of the prize to each winner, in draw order.
+		LotteryDistributed(T::FungibleTokenId, Vec<T::AccountId>, Balance),
+		/// The memo attached to the transfer deposited just before.
+		TransferMemo(T::FungibleTokenId, T::AccountId, T::AccountId, Vec<u8>),
+		/// The fallback owner of a token took it over from the inactive owner.
+		FallbackTriggered(T::FungibleTokenId, T::AccountId, T::AccountId),
+		/// A bridge minted the amount to the account for the external reference.
+		BridgeMinted(T::FungibleTokenId, T::AccountId, Balance, H256),
+		/// A payment stream of the token was created from the first account to the second,
+		/// for the amount between the two blocks.
+		StreamCreated(
+			u64,
+			T::FungibleTokenId,
+			T::AccountId,
+			T::AccountId,
+			Balance,
+			T::BlockNumber,
+			T::BlockNumber,
+		),
+		/// The recipient of a payment stream withdrew the amount.
+		StreamWithdrawn(u64, Balance),
+		/// A payment stream was cancelled, paying the first amount to the recipient and
+		/// returning the second to the sender.
+		StreamCancelled(u64, Balance, Balance),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		Unknown,
+		NoAvailableTokenId,
+		NumOverflow,
+		NoPermission,
+		NotOwner,
+		InvalidId,
+		AmountExceedAllowance,
+		BadMetadata,
+		InsufficientAuthorizedTokens,
+		InsufficientTokens,
+		ConfuseBehavior,
+		ApproveToCurrentOwner,
+		TooManyTransfers,
+		DuplicateSymbol,
+		InvalidFee,
+		SlippageExceeded,
+		MetadataFrozen,
+		TokenNotEmpty,
+		TokenStillActive,
+		AllowanceBelowZero,
+		NotOperator,
+		OperatorCapExceeded,
+		InvalidAccount,
+		AllowanceCapExceeded,
+		TransferNotAllowed,
+		IdInUse,
+		InvalidOrder,
+		UnknownOrder,
+		OrderOverfilled,
+		HolderLimitExceeded,
+		InvalidLottery,
+		TooManyHolders,
+		TransferCooldown,
+		MemoRequired,
+		MemoTooLong,
+		TooManyMints,
+		PalletAccountRecipient,
+		UnsafeRecipient,
+		InvalidSplit,
+		SupplyCapExceeded,
+		CapBelowSupply,
+		CapIncrease,
+		FractionalMintNotAllowed,
+		NoFallbackOwner,
+		OwnerStillActive,
+		TooManyApprovals,
+		TooManyEmissions,
+		AlreadyProcessed,
+		DecimalsMismatch,
+		UnknownStream,
+		InvalidStream,
+		NothingToWithdraw,
+		InvalidFeeSplit,
+		ExceedsMaxTx,
+		ExceedsMaxWallet,
+		InvalidDecimals,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			Self::process_emissions()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn create_token(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::exists(id.clone()), Error::<T>::InvalidId);
+			Self::do_create_token(&who, id, name, symbol, decimals)?;
+			Self::reserve_creation_deposit(&who, id)
+		}
+
+		/// Create a token under the next free id and register its symbol, which must not
+		/// already be taken by another named token.
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn create_token_named(
+			origin: OriginFor<T>,
+			symbol: Vec<u8>,
+			name: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let id = Self::do_create_token_named(&who, symbol, name, decimals)?;
+			Self::reserve_creation_deposit(&who, id)
+		}
+
+		/// Create a token under the next free id with `DefaultDecimals` decimals.
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn create_token_default(
+			origin: OriginFor<T>,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let id = Self::next_token_id()?;
+			Self::do_create_token(&who, id, name, symbol, T::DefaultDecimals::get())?;
+			Self::reserve_creation_deposit(&who, id)
+		}
+
+		/// Create a token under the next free id, unless the caller already created one with
+		/// `symbol` this way, in which case nothing happens. Retrying the call can't create a
+		/// second token or reserve a second deposit.
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn create_token_idempotent(
+			origin: OriginFor<T>,
+			symbol: Vec<u8>,
+			name: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_create_token_idempotent(&who, symbol, name, decimals)?;
+			Ok(())
+		}
+
+		/// Create a token under an id derived from the caller and `salt`, so its id and EVM
+		/// address are known before it exists. See `deterministic_token_id`.
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn create_deterministic(
+			origin: OriginFor<T>,
+			salt: [u8; 32],
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let id = Self::deterministic_token_id(&who, &salt);
+			ensure!(!Self::exists(id), Error::<T>::IdInUse);
+			Self::do_create_token(&who, id, name, symbol, decimals)?;
+			Self::reserve_creation_deposit(&who, id)
+		}
+
+		/// Increase the allowance of `spender`, depositing `Event::Approval` with the new
+		/// allowance. When called through the EVM precompile, the precompile additionally emits
+		/// the ERC20 `Approval` log; a plain extrinsic has no EVM frame and only the pallet
+		/// event is deposited.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			spender: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_approve(id, &who, &spender, amount)
+		}
+
+		/// Like `approve`, but the whole allowance of `spender` is cleared by its next
+		/// `transfer_from`, however much of it is spent.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve_once(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			spender: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_approve(id, &who, &spender, amount)?;
+			if !amount.is_zero() {
+				SingleUseApprovals::<T>::insert(id, (&who, &spender), true);
+			}
+			Ok(())
+		}
+
+		/// Raise the allowance of `spender` by `added_value`, like OpenZeppelin's
+		/// `increaseAllowance`. Unlike `approve` the caller's balance isn't checked.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn increase_allowance(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			spender: T::AccountId,
+			added_value: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_change_allowance(id, &who, &spender, |allowance| {
+				allowance.checked_add(added_value).ok_or(Error::<T>::NumOverflow)
+			})
+		}
+
+		/// Authorize `operator` to transfer up to `cap` out of the caller's balance through
+		/// `operator_transfer`. Setting a cap resets what the operator has spent, a zero cap
+		/// revokes the operator. Operators are independent of allowances.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn set_operator(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			operator: T::AccountId,
+			cap: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_set_operator(id, &who, &operator, cap)
+		}
+
+		/// Transfer `amount` from `from` to `to` as an operator of `from`.
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn operator_transfer(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_operator_transfer(id, &who, &from, &to, amount)
+		}
+
+		/// Lower the allowance of `spender` by `subtracted_value`, failing rather than going
+		/// below zero.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn decrease_allowance(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			spender: T::AccountId,
+			subtracted_value: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_change_allowance(id, &who, &spender, |allowance| {
+				allowance.checked_sub(subtracted_value).ok_or(Error::<T>::AllowanceBelowZero)
+			})
+		}
+
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_memo_not_required(id)?;
+			Self::do_transfer(id, &who, &recipient, amount)
+		}
+
+		/// Like `transfer`, but fails for recipients that can't make use of the tokens: the zero
+		/// account, the pallet's own accounts and `UnsafeRecipients`.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn safe_transfer(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_memo_not_required(id)?;
+			Self::ensure_safe_recipient(&recipient)?;
+			Self::do_transfer(id, &who, &recipient, amount)
+		}
+
+		/// Like `transfer`, with a `memo` of at most `StringLimit` bytes deposited in a
+		/// `TransferMemo` event, such as the tag an exchange attributes deposits by.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer_with_memo(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			amount: Balance,
+			memo: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			ensure!(memo.len() <= T::StringLimit::get() as usize, Error::<T>::MemoTooLong);
+			Self::do_transfer(id, &who, &recipient, amount)?;
+			Self::deposit_event(Event::TransferMemo(id, who, recipient, memo));
+			Ok(())
+		}
+
+		/// Transfer the caller's whole balance, less the token's minimum balance if
+		/// `keep_alive` is set. Does nothing when there is nothing to send.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer_all(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_memo_not_required(id)?;
+
+			let mut amount = Self::spendable_balance(id, &who);
+			if keep_alive {
+				amount = amount.saturating_sub(
+					<Self as fungibles::Inspect<T::AccountId>>::minimum_balance(id),
+				);
+			}
+			if amount.is_zero() {
+				return Ok(())
+			}
+
+			Self::do_transfer(id, &who, &recipient, amount)
+		}
+
+		/// Like `transfer`, but fails unless the recipient receives at least `min_received`
+		/// after the transfer and reflection fees are taken.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer_checked(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			amount: Balance,
+			min_received: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_memo_not_required(id)?;
+			let net = amount.saturating_sub(Self::transfer_fee(id, &who, amount));
+			let net = net.saturating_sub(Self::reflection_fee(id, net));
+			ensure!(net >= min_received, Error::<T>::SlippageExceeded);
+			Self::do_transfer(id, &who, &recipient, amount)
+		}
+
+		/// Transfer to many recipients at once. With `aggregate` set a single `BatchTransfer`
+		/// event is deposited instead of one `Transfer` event per recipient.
+		#[pallet::weight(T::WeightInfo::transfer().saturating_mul(recipients.len() as Weight))]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipients: Vec<(T::AccountId, Balance)>,
+			aggregate: bool,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				Self::exists(id),
+				Error::<T>::InvalidId.with_weight(T::DbWeight::get().reads(1))
+			);
+			ensure!(
+				recipients.len() <= T::MaxBatchTransfers::get() as usize,
+				Error::<T>::TooManyTransfers.with_weight(T::DbWeight::get().reads(1))
+			);
+			Self::ensure_memo_not_required(id)
+				.map_err(|e| e.with_weight(T::DbWeight::get().reads(1)))?;
+			Self::do_transfer_batch(id, &who, recipients, aggregate)?;
+			Ok(().into())
+		}
+
+		/// Split `amount` between recipients by the given fractions, which must add up to one.
+		/// Rounding leftovers go to the last recipient, so exactly `amount` is sent.
+		#[pallet::weight(T::WeightInfo::transfer().saturating_mul(recipients.len() as Weight))]
+		pub fn transfer_split(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipients: Vec<(T::AccountId, Perbill)>,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_memo_not_required(id)?;
+			Self::do_transfer_split(id, &who, recipients, amount)
+		}
+
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			sender: T::AccountId,
+			recipient: T::AccountId,
+			amount: Balance,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				Self::exists(id),
+				Error::<T>::InvalidId.with_weight(T::DbWeight::get().reads(1))
+			);
+			// Only charge for the reads done so far when the checks reject the transfer.
+			Self::validate_transfer_from(id, &who, &sender, &recipient, amount)
+				.map_err(|e| e.with_weight(Self::transfer_from_validation_weight()))?;
+			Self::do_transfer_from(id, who, sender, recipient, amount)?;
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub fn mint(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			account: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id.clone()), Error::<T>::InvalidId);
+			Self::do_mint(id, &who, account, amount)?;
+			Self::charge_mint_fee(&who, 1)
+		}
+
+		/// Mint to many accounts at once. Either every account is credited or none is.
+		#[pallet::weight(T::WeightInfo::mint().saturating_mul(mints.len() as Weight))]
+		pub fn mint_batch(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			mints: Vec<(T::AccountId, Balance)>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			let count = mints.len() as u32;
+			Self::do_mint_batch(id, &who, mints)?;
+			Self::charge_mint_fee(&who, count)
+		}
+
+		/// Mint `amount` to `to` and set the allowance of `spender` over `to`'s tokens to
+		/// `allowance`. Setting an allowance on another account's behalf is only allowed to
+		/// the token owner, and only together with a mint.
+		#[pallet::weight(T::WeightInfo::mint().saturating_add(T::WeightInfo::approve()))]
+		pub fn mint_and_approve(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			to: T::AccountId,
+			amount: Balance,
+			spender: T::AccountId,
+			allowance: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			ensure!(spender != to, Error::<T>::ApproveToCurrentOwner);
+
+			Self::ensure_allowance_within_cap(id, allowance)?;
+			Self::do_mint(id, &who, to.clone(), amount)?;
+			Self::charge_mint_fee(&who, 1)?;
+
+			Self::set_allowance(id, &to, &spender, allowance)?;
+			Self::deposit_event(Event::Approval(id, to, spender, allowance));
+
+			Ok(())
+		}
+
+		/// Replace the name and symbol of a token. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::create_token(name.len() as u32, symbol.len() as u32))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Self::do_set_metadata(id, name, symbol)
+		}
+
+		/// Permanently lock the name and symbol of a token. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn freeze_metadata(origin: OriginFor<T>, id: T::FungibleTokenId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+
+			MetadataFrozen::<T>::insert(id, true);
+
+			Self::deposit_event(Event::MetadataFrozen(id));
+
+			Ok(())
+		}
+
+		/// Set the transfer fee of a token in basis points. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config().saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+		pub fn set_transfer_fee(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			fee_bps: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			ensure!(fee_bps <= MAX_BPS, Error::<T>::InvalidFee);
+			if TransferFeeBps::<T>::get(id) != fee_bps {
+				// The split was sized for the old fee.
+				FeeSplits::<T>::remove(id);
+			}
+			TransferFeeBps::<T>::insert(id, fee_bps);
+			Ok(())
+		}
+
+		/// Split the transfer fee of a token between `splits`, each share in basis points of
+		/// the transferred amount, or send it back to the owner with an empty list. The shares
+		/// must add up to the transfer fee, and a share for the zero account is burned.
+		/// Changing the fee clears the split. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config().saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn set_fee_split(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			splits: Vec<(T::AccountId, u16)>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			if splits.is_empty() {
+				FeeSplits::<T>::remove(id);
+				return Ok(())
+			}
+			let total_bps = splits
+				.iter()
+				.try_fold(0u16, |total, (_, bps)| total.checked_add(*bps))
+				.ok_or(Error::<T>::InvalidFeeSplit)?;
+			ensure!(
+				total_bps == TransferFeeBps::<T>::get(id) &&
+					splits.iter().all(|(_, bps)| !bps.is_zero()),
+				Error::<T>::InvalidFeeSplit
+			);
+			let splits: BoundedVec<_, T::MaxFeeSplits> =
+				splits.try_into().map_err(|_| Error::<T>::InvalidFeeSplit)?;
+			FeeSplits::<T>::insert(id, splits);
+			Ok(())
+		}
+
+		/// Restrict who may send and receive a token. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_transfer_policy(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			policy: TransferPolicy,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.transfer_policy = policy;
+				}
+			});
+			Ok(())
+		}
+
+		/// Cap the number of accounts holding a token, or lift the cap with `None`. Only new
+		/// holders are turned away once the cap is reached. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_max_holders(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			max_holders: Option<u32>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.max_holders = max_holders;
+				}
+			});
+			Ok(())
+		}
+
+		/// Lower the most that can ever be minted of a token to `new_cap`, which can't be below
+		/// the current supply. An uncapped token can be given any such cap, but a cap can never
+		/// be raised or lifted again. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn reduce_cap(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			new_cap: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::try_mutate(id, |maybe_token| -> DispatchResult {
+				let token = maybe_token.as_mut().ok_or(Error::<T>::InvalidId)?;
+				ensure!(new_cap >= token.total_supply, Error::<T>::CapBelowSupply);
+				ensure!(
+					token.max_supply.map_or(true, |cap| new_cap <= cap),
+					Error::<T>::CapIncrease
+				);
+				token.max_supply = Some(new_cap);
+				Ok(())
+			})
+		}
+
+		/// Only allow mints of whole units, multiples of `10^decimals`, or allow any amount
+		/// again. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_whole_units_only(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			whole_units_only: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.whole_units_only = whole_units_only;
+				}
+			});
+			Ok(())
+		}
+
+		/// Stop depositing `Transfer` for transfers between accounts of the token, or deposit it
+		/// again. Balances move the same either way, and fee, mint and burn events are kept.
+		/// Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_emit_transfer_events(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			emit_transfer_events: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.emit_transfer_events = emit_transfer_events;
+				}
+			});
+			Ok(())
+		}
+
+		/// Make plain transfers of the token fail, leaving `transfer_with_memo`, or allow them
+		/// again. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_require_memo(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			require_memo: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.require_memo = require_memo;
+				}
+			});
+			Ok(())
+		}
+
+		/// Make accounts wait `cooldown` blocks after sending the token before sending it again,
+		/// or lift the wait with `None`. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_transfer_cooldown(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			cooldown: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.transfer_cooldown = cooldown;
+				}
+			});
+			Ok(())
+		}
+
+		/// Reject transfers of more than `max_tx_amount` of the token between accounts, or lift
+		/// the limit with `None`. Mints, burns and rescues aren't limited. Only the token owner
+		/// may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_max_tx_amount(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			max_tx_amount: Option<Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.max_tx_amount = max_tx_amount;
+				}
+			});
+			Ok(())
+		}
+
+		/// Reject transfers and mints that would leave an account holding more than
+		/// `max_wallet` of the token, or lift the limit with `None`. Balances already above it
+		/// are kept but can't grow. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_max_wallet(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			max_wallet: Option<Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.max_wallet = max_wallet;
+				}
+			});
+			Ok(())
+		}
+
+		/// Let `who`, e.g. a liquidity pool, hold more of a token than its `max_wallet`, or
+		/// hold it to the limit again. The owner is always exempt. Only the token owner may do
+		/// this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_max_wallet_exempt(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			who: T::AccountId,
+			exempt: bool,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &owner)?;
+			if exempt {
+				MaxWalletExempt::<T>::insert(id, who, true);
+			} else {
+				MaxWalletExempt::<T>::remove(id, who);
+			}
+			Ok(())
+		}
+
+		/// Deposit `SupplyThresholdReached` the first time a mint takes the total supply to
+		/// `threshold` or beyond, or stop watching with `None`. Burning back below doesn't
+		/// re-arm it, setting a threshold again does. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config().saturating_add(T::DbWeight::get().writes(1)))]
+		pub fn set_supply_threshold(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			threshold: Option<Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			SupplyThresholds::<T>::set(id, threshold);
+			SupplyThresholdReached::<T>::remove(id);
+			Ok(())
+		}
+
+		/// Let `fallback_owner` take over the token once the owner has taken no owner action for
+		/// more than `inactivity_blocks`, or remove the fallback with `None`. Only the token owner
+		/// may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_fallback_owner(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			fallback_owner: Option<T::AccountId>,
+			inactivity_blocks: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			FallbackOwners::<T>::set(
+				id,
+				fallback_owner.map(|fallback| (fallback, inactivity_blocks)),
+			);
+			Ok(())
+		}
+
+		/// Hand the token to its fallback owner if the owner has been inactive for longer than
+		/// the configured window. Anyone may do this.
+		#[pallet::weight(T::WeightInfo::set_config().saturating_add(T::DbWeight::get().reads_writes(2, 1)))]
+		pub fn trigger_fallback(origin: OriginFor<T>, id: T::FungibleTokenId) -> DispatchResult {
+			ensure_signed(origin)?;
+			Self::do_trigger_fallback(id)
+		}
+
+		/// Mint `per_block` to `recipient` at the start of every block until the token's
+		/// `max_supply`, if any, is reached, or stop with a zero `per_block`. Only the token
+		/// owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config().saturating_add(T::DbWeight::get().reads_writes(2, 1)))]
+		pub fn set_emission(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			per_block: Balance,
+			recipient: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			if per_block.is_zero() {
+				Emissions::<T>::remove(id);
+			} else {
+				ensure!(
+					Emissions::<T>::contains_key(id) ||
+						Emissions::<T>::count() < T::MaxEmissions::get(),
+					Error::<T>::TooManyEmissions
+				);
+				Emissions::<T>::insert(id, (recipient, per_block));
+			}
+			Ok(())
+		}
+
+		/// Add `who` to, or remove it from, the allowlist or blocklist of a token. Only the
+		/// token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_account_listed(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			who: T::AccountId,
+			listed: bool,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &owner)?;
+			if listed {
+				ListedAccounts::<T>::insert(id, who, true);
+			} else {
+				ListedAccounts::<T>::remove(id, who);
+			}
+			Ok(())
+		}
+
+		/// Set the share of each transfer, in basis points, redistributed to the holders of a
+		/// token. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_reflection_fee(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			fee_bps: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			ensure!(fee_bps <= MAX_BPS, Error::<T>::InvalidFee);
+			ReflectionFeeBps::<T>::insert(id, fee_bps);
+			Ok(())
+		}
+
+		/// Pay out the reflections the caller has accrued on a token.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn claim_reflections(origin: OriginFor<T>, id: T::FungibleTokenId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_claim_reflections(id, &who)
+		}
+
+		/// Cap the allowance any spender may be granted on a token, or lift the cap with
+		/// `None`. Existing allowances are left as they are. Only the token owner may do this.
+		#[pallet::weight(T::WeightInfo::set_config())]
+		pub fn set_max_allowance(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			max_allowance: Option<Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::maybe_check_permission(id, &who)?;
+			Tokens::<T>::mutate(id, |token| {
+				if let Some(token) = token {
+					token.max_allowance = max_allowance;
+				}
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_burn(id, &who, amount)
+		}
+
+		/// Destroy a token that has no supply and saw no transfer, mint or burn for longer
+		/// than `InactivityThreshold`, returning the creation deposit to its creator.
+		///
+		/// Each call removes at most `MAX_REAP_REMOVALS` balances, allowances and other
+		/// per-account entries. The token is gone after the first call, and while `is_reaping`
+		/// is set the call has to be repeated to clear the rest.
+		#[pallet::weight(
+			T::WeightInfo::burn().saturating_add(T::DbWeight::get().reads_writes(
+				MAX_REAP_REMOVALS as Weight,
+				MAX_REAP_REMOVALS.saturating_add(5) as Weight,
+			))
+		)]
+		pub fn reap_inactive(origin: OriginFor<T>, id: T::FungibleTokenId) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			Self::do_reap_inactive(id)
+		}
+
+		/// Recover tokens stuck in `from`, e.g. sent to a pallet or contract account by
+		/// mistake, by moving them to `to`. Allowances, transfer fees and the transfer policy
+		/// don't apply.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn rescue_tokens(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_rescue_tokens(id, &from, &to, amount)
+		}
+
+		/// Move whatever the pallet account holds of a token beyond the reflections it owes to
+		/// `to`, recovering tokens sent to it by mistake. Escrowed orders and streams are held
+		/// by their own accounts and aren't touched.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn sweep_pallet_balance(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			to: T::AccountId,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::ensure_safe_recipient(&to)?;
+			let amount = Self::sweepable_balance(id);
+			ensure!(!amount.is_zero(), Error::<T>::NothingToWithdraw);
+			Self::do_rescue_tokens(id, &Self::reflection_account_id(), &to, amount)
+		}
+
+		/// Mint `amount` to `to` for tokens locked elsewhere, identified by `external_ref`. Each
+		/// reference is only minted for once per token.
+		#[pallet::weight(T::WeightInfo::mint().saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+		pub fn bridge_mint(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			to: T::AccountId,
+			amount: Balance,
+			external_ref: H256,
+		) -> DispatchResult {
+			T::BridgeOrigin::ensure_origin(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_bridge_mint(id, &to, amount, external_ref)
+		}
+
+		/// Offer `sell_amount` of `sell_id` for `buy_amount` of `buy_id`, moving the sell side
+		/// into escrow until the order is filled or cancelled.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn place_order(
+			origin: OriginFor<T>,
+			sell_id: T::FungibleTokenId,
+			sell_amount: Balance,
+			buy_id: T::FungibleTokenId,
+			buy_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(sell_id) && Self::exists(buy_id), Error::<T>::InvalidId);
+			Self::do_place_order(&who, sell_id, sell_amount, buy_id, buy_amount).map(|_| ())
+		}
+
+		/// Buy `fill_amount` of an order's sell side at the order's price, rounded up in
+		/// favour of the maker.
+		#[pallet::weight(T::WeightInfo::transfer().saturating_mul(2))]
+		pub fn fill_order(
+			origin: OriginFor<T>,
+			order_id: u64,
+			fill_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_fill_order(&who, order_id, fill_amount)
+		}
+
+		/// Cancel an order and return its unfilled escrow. Only the maker may do this.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn cancel_order(origin: OriginFor<T>, order_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_cancel_order(&who, order_id)
+		}
+
+		/// Pay `total` to `recipient` linearly from `start_block` to `end_block`, moving it into
+		/// escrow until withdrawn or cancelled.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn create_stream(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			recipient: T::AccountId,
+			total: Balance,
+			start_block: T::BlockNumber,
+			end_block: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			Self::do_create_stream(&who, id, &recipient, total, start_block, end_block).map(|_| ())
+		}
+
+		/// Withdraw what a stream has vested so far and not been withdrawn yet. Only the
+		/// recipient may do this.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn withdraw_from_stream(origin: OriginFor<T>, stream_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_withdraw_from_stream(&who, stream_id)
+		}
+
+		/// End a stream, paying the recipient what has vested and returning the rest. Only the
+		/// sender may do this.
+		#[pallet::weight(T::WeightInfo::transfer().saturating_mul(2))]
+		pub fn cancel_stream(origin: OriginFor<T>, stream_id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_cancel_stream(&who, stream_id)
+		}
+
+		/// Pay an equal share of `prize_amount` from the owner to each of `winners_count`
+		/// distinct holders, drawn with a chance proportional to their balance. Only the token
+		/// owner may do this.
+		#[pallet::weight(
+			T::WeightInfo::transfer()
+				.saturating_mul(*winners_count as Weight)
+				.saturating_add(T::DbWeight::get().reads(T::MaxLotteryHolders::get() as Weight))
+		)]
+		pub fn lottery_distribute(
+			origin: OriginFor<T>,
+			id: T::FungibleTokenId,
+			prize_amount: Balance,
+			winners_count: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_lottery_distribute(&who, id, prize_amount, winners_count).map(|_| ())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Bump and return the global event sequence number.
+	fn next_event_seq() -> u64 {
+		EventSeq::<T>::mutate(|seq| {
+			*seq = seq.saturating_add(1);
+			*seq
+		})
+	}
+
+	/// Stable numeric code of a pallet error, for clients that can't match on names. Codes
+	/// are never reused or renumbered: new errors get the next free code.
+	pub fn error_code(err: &Error<T>) -> u8 {
+		match err {
+			Error::Unknown => 0,
+			Error::NoAvailableTokenId => 1,
+			Error::NumOverflow => 2,
+			Error::NoPermission => 3,
+			Error::NotOwner => 4,
+			Error::InvalidId => 5,
+			Error::AmountExceedAllowance => 6,
+			Error::BadMetadata => 7,
+			Error::InsufficientAuthorizedTokens => 8,
+			Error::InsufficientTokens => 9,
+			Error::ConfuseBehavior => 10,
+			Error::ApproveToCurrentOwner => 11,
+			Error::TooManyTransfers => 12,
+			Error::DuplicateSymbol => 13,
+			Error::InvalidFee => 14,
+			Error::SlippageExceeded => 15,
+			Error::MetadataFrozen => 16,
+			Error::TokenNotEmpty => 17,
+			Error::TokenStillActive => 18,
+			Error::AllowanceBelowZero => 19,
+			Error::NotOperator => 20,
+			Error::OperatorCapExceeded => 21,
+			Error::InvalidAccount => 22,
+			Error::AllowanceCapExceeded => 23,
+			Error::TransferNotAllowed => 24,
+			Error::IdInUse => 25,
+			Error::InvalidOrder => 26,
+			Error::UnknownOrder => 27,
+			Error::OrderOverfilled => 28,
+			Error::HolderLimitExceeded => 29,
+			Error::InvalidLottery => 30,
+			Error::TooManyHolders => 31,
+			Error::TransferCooldown => 32,
+			Error::MemoRequired => 33,
+			Error::MemoTooLong => 34,
+			Error::TooManyMints => 35,
+			Error::PalletAccountRecipient => 36,
+			Error::UnsafeRecipient => 37,
+			Error::InvalidSplit => 38,
+			Error::SupplyCapExceeded => 39,
+			Error::CapBelowSupply => 40,
+			Error::CapIncrease => 41,
+			Error::FractionalMintNotAllowed => 42,
+			Error::NoFallbackOwner => 43,
+			Error::OwnerStillActive => 44,
+			Error::TooManyApprovals => 45,
+			Error::TooManyEmissions => 46,
+			Error::AlreadyProcessed => 47,
+			Error::DecimalsMismatch => 48,
+			Error::UnknownStream => 49,
+			Error::InvalidStream => 50,
+			Error::NothingToWithdraw => 51,
+			Error::InvalidFeeSplit => 52,
+			Error::ExceedsMaxTx => 53,
+			Error::ExceedsMaxWallet => 54,
+			Error::InvalidDecimals => 55,
+			Error::__Ignore(_, never) => match *never {},
+		}
+	}
+
+	fn zero_account_id() -> T::AccountId {
+		T::AccountId::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed")
+	}
+
+	pub fn exists(id: T::FungibleTokenId) -> bool {
+		Tokens::<T>::contains_key(id)
+	}
+
+	pub fn total_supply(id: T::FungibleTokenId) -> Balance {
+		Tokens::<T>::get(id).unwrap().total_supply
+	}
+
+	/// The sum of the allowances `owner` granted, saturating at `Balance::MAX`, so one
+	/// unlimited approval makes the total unlimited too. This walks at most
+	/// `MAX_TOTAL_APPROVED_SCAN` allowances of the whole token and is meant for the runtime API,
+	/// not for on-chain use.
+	pub fn total_approved(id: T::FungibleTokenId, owner: &T::AccountId) -> Balance {
+		Allowances::<T>::iter_prefix(id)
+			.take(MAX_TOTAL_APPROVED_SCAN as usize)
+			.filter(|((granter, _), _)| granter == owner)
+			.fold(Zero::zero(), |total: Balance, (_, amount)| total.saturating_add(amount))
+	}
+
+	/// Fail unless both tokens exist and have the same decimals, for callers doing math across
+	/// the two amounts.
+	pub fn assert_same_decimals(
+		id_a: T::FungibleTokenId,
+		id_b: T::FungibleTokenId,
+	) -> DispatchResult {
+		let a = Tokens::<T>::get(id_a).ok_or(Error::<T>::InvalidId)?;
+		let b = Tokens::<T>::get(id_b).ok_or(Error::<T>::InvalidId)?;
+		ensure!(a.decimals == b.decimals, Error::<T>::DecimalsMismatch);
+		Ok(())
+	}
+
+	/// What `who` can move of its balance of a token. The pallet doesn't reserve, lock or
+	/// freeze balances, so this is the whole balance for now; `transfer_all` and
+	/// `reducible_balance` go through it so such holds only have to be subtracted here.
+	pub fn spendable_balance(id: T::FungibleTokenId, who: &T::AccountId) -> Balance {
+		Balances::<T>::get(id, who)
+	}
+
+	/// The block a token was created in.
+	pub fn created_at(id: T::FungibleTokenId) -> Option<T::BlockNumber> {
+		Tokens::<T>::get(id).map(|token| token.created_at)
+	}
+
+	/// The largest allowance the token permits, `None` if uncapped.
+	pub fn max_allowance(id: T::FungibleTokenId) -> Option<Balance> {
+		Tokens::<T>::get(id).and_then(|token| token.max_allowance)
+	}
+
+	/// The balance of `who` as a UTF-8 decimal string using the token's decimals, with
+	/// trailing fractional zeros trimmed (e.g. `1500000` with 6 decimals is `"1.5"`).
+	pub fn formatted_balance(id: T::FungibleTokenId, who: &T::AccountId) -> Vec<u8> {
+		let decimals = Tokens::<T>::get(id).map(|token| token.decimals).unwrap_or_default();
+		Self::format_balance(Balances::<T>::get(id, who), decimals)
+	}
+
+	pub fn format_balance(amount: Balance, decimals: u8) -> Vec<u8> {
+		let decimals = decimals as usize;
+
+		let mut digits = Vec::new();
+		let mut rest = amount;
+		loop {
+			digits.push(b'0' + (rest % 10) as u8);
+			rest /= 10;
+			if rest == 0 {
+				break
+			}
+		}
+		// Keep at least one integer digit in front of the fraction.
+		digits.resize(digits.len().max(decimals + 1), b'0');
+		digits.reverse();
+
+		let (integer, fraction) = digits.split_at(digits.len() - decimals);
+		let fraction_len = fraction.iter().rposition(|d| *d != b'0').map_or(0, |i| i + 1);
+
+		let mut formatted = integer.to_vec();
+		if fraction_len > 0 {
+			formatted.push(b'.');
+			formatted.extend_from_slice(&fraction[..fraction_len]);
+		}
+		formatted
+	}
+
+	/// `whole` units in base units of a token with `decimals` decimals, i.e.
+	/// `whole * 10^decimals`, or `None` on overflow. The pallet never scales amounts itself:
+	/// every amount it takes or returns is in base units.
+	pub fn scale_amount(whole: Balance, decimals: u8) -> Option<Balance> {
+		Balance::from(10u8).checked_pow(decimals.into())?.checked_mul(whole)
+	}
+
+	/// The allowances `owner` granted to each of `spenders`, in order. Spenders beyond
+	/// `MAX_ALLOWANCE_BATCH` are ignored.
+	pub fn allowance_batch(
+		id: T::FungibleTokenId,
+		owner: &T::AccountId,
+		spenders: Vec<T::AccountId>,
+	) -> Vec<Balance> {
+		spenders
+			.iter()
+			.take(MAX_ALLOWANCE_BATCH as usize)
+			.map(|spender| Allowances::<T>::get(id, (owner, spender)))
+			.collect()
+	}
+
+	/// Page through `Tokens` in storage order, starting after `start` (or from the
+	/// beginning when `None`) and returning at most `limit` entries.
+	pub fn all_tokens(
+		start: Option<T::FungibleTokenId>,
+		limit: u32,
+	) -> Vec<(T::FungibleTokenId, TokenInfo<T::AccountId, T::BlockNumber>)> {
+		let iter = match start {
+			Some(id) => Tokens::<T>::iter_from(Tokens::<T>::hashed_key_for(id)),
+			None => Tokens::<T>::iter(),
+		};
+
+		iter.take(limit as usize)
+			.map(|(id, token)| (id, Self::info_of(token)))
+			.collect()
+	}
+
+	/// The metadata of `id`, if it exists.
+	pub fn token_info(id: T::FungibleTokenId) -> Option<TokenInfo<T::AccountId, T::BlockNumber>> {
+		Tokens::<T>::get(id).map(Self::info_of)
+	}
+
+	fn info_of(
+		token: Token<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+	) -> TokenInfo<T::AccountId, T::BlockNumber> {
+		Token {
+			owner: token.owner,
+			name: token.name.into_inner(),
+			symbol: token.symbol.into_inner(),
+			decimals: token.decimals,
+			total_supply: token.total_supply,
+			created_at: token.created_at,
+			last_activity: token.last_activity,
+			max_allowance: token.max_allowance,
+			transfer_policy: token.transfer_policy,
+			max_holders: token.max_holders,
+			transfer_cooldown: token.transfer_cooldown,
+			require_memo: token.require_memo,
+			max_supply: token.max_supply,
+			whole_units_only: token.whole_units_only,
+			emit_transfer_events: token.emit_transfer_events,
+			max_tx_amount: token.max_tx_amount,
+			max_wallet: token.max_wallet,
+		}
+	}
+
+	pub fn do_create_token(
+		who: &T::AccountId,
+		id: T::FungibleTokenId,
+		name: Vec<u8>,
+		symbol: Vec<u8>,
+		decimals: u8,
+	) -> DispatchResult {
+		ensure!(!Reaping::<T>::get(id), Error::<T>::InvalidId);
+		let (bounded_name, bounded_symbol) = Self::validate_metadata(name, symbol)?;
+
+		let token = Token::new(
+			who.clone(),
+			bounded_name.clone(),
+			bounded_symbol.clone(),
+			decimals,
+			frame_system::Pallet::<T>::block_number(),
+		)
+		.ok_or(Error::<T>::InvalidDecimals)?;
+
+		Tokens::<T>::insert(id, token);
+		TokenCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+		Self::deposit_event(Event::TokenCreated(
+			id,
+			who.clone(),
+			bounded_name.into_inner(),
+			bounded_symbol.into_inner(),
+			decimals,
+		));
+
+		Ok(())
+	}
+
+	fn reserve_creation_deposit(who: &T::AccountId, id: T::FungibleTokenId) -> DispatchResult {
+		let deposit = T::CreateTokenDeposit::get();
+		T::Currency::reserve(who, deposit)?;
+		TokenDeposits::<T>::insert(id, (who.clone(), deposit));
+
+		Self::deposit_event(Event::DepositReserved(id, who.clone(), deposit));
+
+		Ok(())
+	}
+
+	pub fn do_reap_inactive(id: T::FungibleTokenId) -> DispatchResult {
+		if !Reaping::<T>::get(id) {
+			Self::remove_token(id)?;
+			Reaping::<T>::insert(id, true);
+		}
+
+		if Self::clear_token_accounts(id, MAX_REAP_REMOVALS) {
+			Reaping::<T>::remove(id);
+			Self::deposit_event(Event::TokenReaped(id));
+		}
+
+		Ok(())
+	}
+
+	/// Remove an inactive token and its per-token settings and return its creation deposit,
+	/// leaving the per-account entries to `clear_token_accounts`.
+	fn remove_token(id: T::FungibleTokenId) -> DispatchResult {
+		let token = Tokens::<T>::get(id).ok_or(Error::<T>::InvalidId)?;
+		ensure!(token.total_supply.is_zero(), Error::<T>::TokenNotEmpty);
+
+		let idle = frame_system::Pallet::<T>::block_number().saturating_sub(token.last_activity);
+		ensure!(idle > T::InactivityThreshold::get(), Error::<T>::TokenStillActive);
+
+		Tokens::<T>::remove(id);
+		let symbol_key = Self::symbol_key(&token.symbol);
+		if SymbolToId::<T>::get(&symbol_key) == Some(id) {
+			SymbolToId::<T>::remove(&symbol_key);
+		}
+		TransferFeeBps::<T>::remove(id);
+		FeeSplits::<T>::remove(id);
+		ReflectionFeeBps::<T>::remove(id);
+		MagnifiedPerShare::<T>::remove(id);
+		UnclaimedReflections::<T>::remove(id);
+		MetadataFrozen::<T>::remove(id);
+		HolderCount::<T>::remove(id);
+		SupplyThresholds::<T>::remove(id);
+		SupplyThresholdReached::<T>::remove(id);
+		FallbackOwners::<T>::remove(id);
+		LastOwnerAction::<T>::remove(id);
+		TotalMinted::<T>::remove(id);
+		TotalBurned::<T>::remove(id);
+		Emissions::<T>::remove(id);
+
+		if let Some((creator, deposit)) = TokenDeposits::<T>::take(id) {
+			if IdempotentTokens::<T>::get(&creator, &token.symbol) == Some(id) {
+				IdempotentTokens::<T>::remove(&creator, &token.symbol);
+			}
+			// Whatever couldn't be unreserved was slashed elsewhere and isn't returned.
+			let missing = T::Currency::unreserve(&creator, deposit);
+			Self::deposit_event(Event::DepositReturned(
+				id,
+				creator,
+				deposit.saturating_sub(missing),
+			));
+		}
+
+		Ok(())
+	}
+
+	/// Remove up to `limit` per-account entries of a reaped token and return whether none are
+	/// left. A re-created token must not inherit balances, allowances, operators or holders; permit
+	/// nonces and processed bridge mints are kept so old signatures and references can't be
+	/// replayed.
+	fn clear_token_accounts(id: T::FungibleTokenId, limit: u32) -> bool {
+		let mut budget = limit as usize;
+		budget -= Balances::<T>::drain_prefix(id).take(budget).count();
+		budget -= Allowances::<T>::drain_prefix(id).take(budget).count();
+		budget -= ApprovalCounts::<T>::drain_prefix(id).take(budget).count();
+		budget -= SingleUseApprovals::<T>::drain_prefix(id).take(budget).count();
+		budget -= OperatorCaps::<T>::drain_prefix(id).take(budget).count();
+		budget -= OperatorSpent::<T>::drain_prefix(id).take(budget).count();
+		budget -= ReflectionCheckpoints::<T>::drain_prefix(id).take(budget).count();
+		budget -= PendingReflections::<T>::drain_prefix(id).take(budget).count();
+		budget -= ListedAccounts::<T>::drain_prefix(id).take(budget).count();
+		budget -= MaxWalletExempt::<T>::drain_prefix(id).take(budget).count();
+		budget -= LastTransfer::<T>::drain_prefix(id).take(budget).count();
+		budget -= EverHeld::<T>::drain_prefix(id).take(budget).count();
+		// An emptied balance already gave its provider back, this only catches leftovers.
+		budget -= ProvidedAccounts::<T>::drain_prefix(id)
+			.take(budget)
+			.map(|(who, provided)| {
+				if provided {
+					let _ = frame_system::Pallet::<T>::dec_providers(&who);
+				}
+			})
+			.count();
+		// Running out exactly on the last entry leaves an empty final call.
+		budget > 0
+	}
+
+	pub fn do_trigger_fallback(id: T::FungibleTokenId) -> DispatchResult {
+		let (fallback, inactivity_blocks) =
+			FallbackOwners::<T>::get(id).ok_or(Error::<T>::NoFallbackOwner)?;
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let idle = now.saturating_sub(LastOwnerAction::<T>::get(id));
+		ensure!(idle > inactivity_blocks, Error::<T>::OwnerStillActive);
+
+		let previous =
+			Tokens::<T>::try_mutate(id, |token| -> Result<T::AccountId, DispatchError> {
+				let token = token.as_mut().ok_or(Error::<T>::InvalidId)?;
+				Ok(sp_std::mem::replace(&mut token.owner, fallback.clone()))
+			})?;
+		FallbackOwners::<T>::remove(id);
+		LastOwnerAction::<T>::insert(id, now);
+
+		Self::deposit_event(Event::FallbackTriggered(id, previous, fallback));
+
+		Ok(())
+	}
+
+	pub fn do_rescue_tokens(
+		id: T::FungibleTokenId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(from != to, Error::<T>::ConfuseBehavior);
+		Self::validate_move_balance(id, from, to, amount)?;
+		Self::move_balance(id, from, to, amount)?;
+
+		Self::deposit_event(Event::Rescued(id, from.clone(), to.clone(), amount));
+
+		Ok(())
+	}
+
+	/// The part of the pallet account's balance of a token that no holder has a claim on.
+	pub fn sweepable_balance(id: T::FungibleTokenId) -> Balance {
+		Balances::<T>::get(id, Self::reflection_account_id())
+			.saturating_sub(UnclaimedReflections::<T>::get(id))
+	}
+
+	/// Record a transfer, mint or burn of `id` at the current block.
+	fn touch(id: T::FungibleTokenId) {
+		let now = frame_system::Pallet::<T>::block_number();
+		Tokens::<T>::mutate(id, |maybe_token| {
+			if let Some(token) = maybe_token {
+				token.last_activity = now;
+			}
+		});
+	}
+
+	pub fn do_set_metadata(
+		id: T::FungibleTokenId,
+		name: Vec<u8>,
+		symbol: Vec<u8>,
+	) -> DispatchResult {
+		ensure!(!MetadataFrozen::<T>::get(id), Error::<T>::MetadataFrozen);
+
+		let (bounded_name, bounded_symbol) = Self::validate_metadata(name, symbol)?;
+
+		Tokens::<T>::try_mutate(id, |maybe_token| -> DispatchResult {
+			let token = maybe_token.as_mut().ok_or(Error::<T>::InvalidId)?;
+
+			// Keep the symbol registry pointing at the token's current symbol.
+			let old_key = Self::symbol_key(&token.symbol);
+			let new_key = Self::symbol_key(&bounded_symbol);
+			if old_key != new_key && SymbolToId::<T>::get(&old_key) == Some(id) {
+				ensure!(!SymbolToId::<T>::contains_key(&new_key), Error::<T>::DuplicateSymbol);
+				SymbolToId::<T>::remove(&old_key);
+				SymbolToId::<T>::insert(&new_key, id);
+			}
+
+			token.name = bounded_name.clone();
+			token.symbol = bounded_symbol.clone();
+			Ok(())
+		})?;
+
+		Self::deposit_event(Event::MetadataUpdated(
+			id,
+			bounded_name.into_inner(),
+			bounded_symbol.into_inner(),
+		));
+
+		Ok(())
+	}
+
+	/// Fit a name or symbol into `StringLimit` according to `OnMetadataOverflow`.
+	fn bound_metadata(value: Vec<u8>) -> Result<BoundedVec<u8, T::StringLimit>, DispatchError> {
+		let mut value = value;
+		if T::OnMetadataOverflow::get() == MetadataOverflowPolicy::Truncate {
+			value.truncate(T::StringLimit::get() as usize);
+		}
+
+		Ok(value.try_into().map_err(|_| Error::<T>::BadMetadata)?)
+	}
+
+	/// Check `name` and `symbol` are long enough and bound them as they would be stored.
+	fn validate_metadata(
+		name: Vec<u8>,
+		symbol: Vec<u8>,
+	) -> Result<(BoundedVec<u8, T::StringLimit>, BoundedVec<u8, T::StringLimit>), DispatchError> {
+		ensure!(
+			name.len() >= T::MinNameLength::get() as usize &&
+				symbol.len() >= T::MinSymbolLength::get() as usize,
+			Error::<T>::BadMetadata
+		);
+		Ok((Self::bound_metadata(name)?, Self::bound_metadata(symbol)?))
+	}
+
+	fn ensure_symbol_available(symbol: &BoundedVec<u8, T::StringLimit>) -> DispatchResult {
+		ensure!(
+			!SymbolToId::<T>::contains_key(Self::symbol_key(symbol)),
+			Error::<T>::DuplicateSymbol
+		);
+		Ok(())
+	}
+
+	/// Run the checks `create_token_named` would apply to the given metadata, without
+	/// reserving a deposit or writing to storage.
+	pub fn validate_create(name: Vec<u8>, symbol: Vec<u8>, decimals: u8) -> DispatchResult {
+		ensure!(decimals <= MAX_DECIMALS, Error::<T>::InvalidDecimals);
+		let (_, symbol) = Self::validate_metadata(name, symbol)?;
+		Self::ensure_symbol_available(&symbol)
+	}
+
+	pub fn do_create_token_named(
+		who: &T::AccountId,
+		symbol: Vec<u8>,
+		name: Vec<u8>,
+		decimals: u8,
+	) -> Result<T::FungibleTokenId, DispatchError> {
+		let bounded_symbol = Self::bound_metadata(symbol.clone())?;
+		Self::ensure_symbol_available(&bounded_symbol)?;
+		let symbol_key = Self::symbol_key(&bounded_symbol);
+
+		let id = Self::next_token_id()?;
+		Self::do_create_token(who, id, name, symbol, decimals)?;
+		SymbolToId::<T>::insert(symbol_key, id);
+
+		Ok(id)
+	}
+
+	/// The id of the token `who` created with `symbol` through `create_token_idempotent`,
+	/// creating it first if there is none.
+	pub fn do_create_token_idempotent(
+		who: &T::AccountId,
+		symbol: Vec<u8>,
+		name: Vec<u8>,
+		decimals: u8,
+	) -> Result<T::FungibleTokenId, DispatchError> {
+		let bounded_symbol = Self::bound_metadata(symbol.clone())?;
+		if let Some(id) = IdempotentTokens::<T>::get(who, &bounded_symbol) {
+			if Self::exists(id) {
+				return Ok(id)
+			}
+		}
+
+		let id = Self::next_token_id()?;
+		Self::do_create_token(who, id, name, symbol, decimals)?;
+		Self::reserve_creation_deposit(who, id)?;
+		IdempotentTokens::<T>::insert(who, bounded_symbol, id);
+
+		Ok(id)
+	}
+
+	/// The id `create_deterministic` assigns for `creator` and `salt`: the hash of both,
+	/// truncated to the id type.
+	pub fn deterministic_token_id(creator: &T::AccountId, salt: &[u8; 32]) -> T::FungibleTokenId {
+		let hash = sp_io::hashing::blake2_256(&(b"w3g/tfid", creator, salt).encode());
+		T::FungibleTokenId::decode(&mut TrailingZeroInput::new(&hash)).expect("infinite input; qed")
+	}
+
+	/// Take the next free token id.
+	fn next_token_id() -> Result<T::FungibleTokenId, DispatchError> {
+		NextTokenId::<T>::try_mutate(|next| -> Result<T::FungibleTokenId, DispatchError> {
+			// Explicit ids passed to `create_token` may already occupy the counter.
+			let mut id = *next;
+			while Self::exists(id) || Reaping::<T>::get(id) {
+				id = id.checked_add(&One::one()).ok_or(Error::<T>::NoAvailableTokenId)?;
+			}
+			*next = id.checked_add(&One::one()).ok_or(Error::<T>::NoAvailableTokenId)?;
+			Ok(id)
+		})
+	}
+
+	/// The ids the next `count` creations without an explicit id would receive, up to
+	/// `MAX_PEEK_TOKEN_IDS`. Fewer are returned when the id space runs out.
+	pub fn peek_token_ids(count: u32) -> Vec<T::FungibleTokenId> {
+		let count = count.min(MAX_PEEK_TOKEN_IDS) as usize;
+		let mut ids = Vec::with_capacity(count);
+		let mut next = NextTokenId::<T>::get();
+		while ids.len() < count {
+			while Self::exists(next) || Reaping::<T>::get(next) {
+				match next.checked_add(&One::one()) {
+					Some(id) => next = id,
+					None => return ids,
+				}
+			}
+			// Like `next_token_id`, the last id is never handed out.
+			match next.checked_add(&One::one()) {
+				Some(after) => {
+					ids.push(next);
+					next = after;
+				},
+				None => break,
+			}
+		}
+		ids
+	}
+
+	/// Resolve a symbol registered through `create_token_named` to its token id.
+	pub fn token_id_by_symbol(symbol: &[u8]) -> Option<T::FungibleTokenId> {
+		let bounded_symbol: BoundedVec<u8, T::StringLimit> = symbol.to_vec().try_into().ok()?;
+		SymbolToId::<T>::get(Self::symbol_key(&bounded_symbol))
+	}
+
+	/// The form of `symbol` used as key of the symbol registry.
+	fn symbol_key(symbol: &BoundedVec<u8, T::StringLimit>) -> BoundedVec<u8, T::StringLimit> {
+		if !T::NormalizeSymbols::get() {
+			return symbol.clone()
+		}
+
+		let start = symbol.iter().position(|c| !c.is_ascii_whitespace()).unwrap_or(symbol.len());
+		let end = symbol.iter().rposition(|c| !c.is_ascii_whitespace()).map_or(start, |i| i + 1);
+		// Normalizing never makes the symbol longer.
+		symbol[start..end]
+			.to_ascii_uppercase()
+			.try_into()
+			.unwrap_or_else(|_| symbol.clone())
+	}
+
+	pub fn do_approve(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		spender: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(spender != who, Error::<T>::ApproveToCurrentOwner);
+
+		ensure!(
+			Balances::<T>::get(id, who.clone()) >= amount,
+			Error::<T>::InsufficientAuthorizedTokens
+		);
+
+		let allowance = Allowances::<T>::get(id, (&who, &spender))
+			.checked_add(amount)
+			.ok_or(Error::<T>::NumOverflow)?;
+		Self::ensure_allowance_within_cap(id, allowance)?;
+		Self::set_allowance(id, who, spender, allowance)?;
+
+		Self::deposit_event(Event::Approval(id, who.clone(), spender.clone(), allowance));
+
+		Ok(())
+	}
+
+	pub fn do_set_operator(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		operator: &T::AccountId,
+		cap: Balance,
+	) -> DispatchResult {
+		ensure!(operator != who, Error::<T>::ApproveToCurrentOwner);
+
+		OperatorSpent::<T>::remove(id, (who, operator));
+		if cap.is_zero() {
+			OperatorCaps::<T>::remove(id, (who, operator));
+		} else {
+			OperatorCaps::<T>::insert(id, (who, operator), cap);
+		}
+
+		Self::deposit_event(Event::OperatorSet(id, who.clone(), operator.clone(), cap));
+
+		Ok(())
+	}
+
+	pub fn do_operator_transfer(
+		id: T::FungibleTokenId,
+		operator: &T::AccountId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		let cap = OperatorCaps::<T>::get(id, (from, operator));
+		ensure!(!cap.is_zero(), Error::<T>::NotOperator);
+
+		let spent = OperatorSpent::<T>::get(id, (from, operator))
+			.checked_add(amount)
+			.ok_or(Error::<T>::NumOverflow)?;
+		ensure!(spent <= cap, Error::<T>::OperatorCapExceeded);
+
+		Self::validate_transfer(id, from, to, amount)?;
+
+		OperatorSpent::<T>::insert(id, (from, operator), spent);
+		Self::internal_transfer(id, from, to, amount)
+	}
+
+	/// Replace the allowance of `spender` with `f` applied to it. Lowering an allowance is
+	/// always allowed, even above the token's `max_allowance`.
+	pub fn do_change_allowance(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		spender: &T::AccountId,
+		f: impl FnOnce(Balance) -> Result<Balance, Error<T>>,
+	) -> DispatchResult {
+		ensure!(spender != who, Error::<T>::ApproveToCurrentOwner);
+
+		let current = Allowances::<T>::get(id, (who, spender));
+		let allowance = f(current)?;
+		if allowance > current {
+			Self::ensure_allowance_within_cap(id, allowance)?;
+		}
+		Self::set_allowance(id, who, spender, allowance)?;
+
+		Self::deposit_event(Event::Approval(id, who.clone(), spender.clone(), allowance));
+
+		Ok(())
+	}
+
+	fn ensure_allowance_within_cap(id: T::FungibleTokenId, allowance: Balance) -> DispatchResult {
+		ensure!(
+			Self::max_allowance(id).map_or(true, |max| allowance <= max),
+			Error::<T>::AllowanceCapExceeded
+		);
+		Ok(())
+	}
+
+	/// Set the allowance of `spender` over `owner`'s tokens to exactly `amount`, as EIP-2612
+	/// asks, and consume the owner's permit nonce. Unlike `approve` this doesn't add to the
+	/// current allowance and doesn't look at the owner's balance. The caller is responsible for
+	/// checking the permit was signed by `owner` for the current nonce.
+	pub fn do_permit(
+		id: T::FungibleTokenId,
+		owner: &T::AccountId,
+		spender: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(Self::exists(id), Error::<T>::InvalidId);
+		ensure!(spender != owner, Error::<T>::ApproveToCurrentOwner);
+		Self::ensure_allowance_within_cap(id, amount)?;
+
+		let nonce = PermitNonces::<T>::get(id, owner)
+			.checked_add(1)
+			.ok_or(Error::<T>::NumOverflow)?;
+
+		Self::set_allowance(id, owner, spender, amount)?;
+		PermitNonces::<T>::insert(id, owner, nonce);
+		Self::deposit_event(Event::Approval(id, owner.clone(), spender.clone(), amount));
+
+		Ok(())
+	}
+
+	pub fn do_transfer_from(
+		id: T::FungibleTokenId,
+		who: T::AccountId,
+		sender: T::AccountId,
+		recipient: T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		Self::validate_transfer_from(id, &who, &sender, &recipient, amount)?;
+
+		let mut allowance = Allowances::<T>::get(id, (&sender, &who))
+			.checked_sub(amount)
+			.ok_or(Error::<T>::NumOverflow)?;
+		if SingleUseApprovals::<T>::get(id, (&sender, &who)) {
+			allowance = Zero::zero();
+		}
+		Self::set_allowance(id, &sender, &who, allowance)?;
+		// Like OpenZeppelin's ERC20, so indexers can follow the allowance as it is spent.
+		Self::deposit_event(Event::Approval(id, sender.clone(), who, allowance));
+
+		Self::internal_transfer(id, &sender, &recipient, amount)?;
+
+		Ok(())
+	}
+
+	pub fn do_transfer(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		Self::validate_transfer(id, who, recipient, amount)?;
+
+		Self::internal_transfer(id, who, recipient, amount)?;
+
+		Ok(())
+	}
+
+	/// Transfer exactly `amount` from `from` to `to` on behalf of another pallet. The same as
+	/// `do_transfer`, named for callers composing it with `transfer_reducible`.
+	pub fn try_transfer(
+		id: T::FungibleTokenId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		Self::do_transfer(id, from, to, amount)
+	}
+
+	/// Transfer as much of `desired` as `from` can spend and return the amount taken from
+	/// `from`. Transfer fees still come out of that amount. Nothing happens if `from` has
+	/// nothing to spend.
+	pub fn transfer_reducible(
+		id: T::FungibleTokenId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		desired: Balance,
+	) -> Result<Balance, DispatchError> {
+		let amount = desired
+			.min(<Self as fungibles::Inspect<T::AccountId>>::reducible_balance(id, from, false));
+		if amount.is_zero() {
+			return Ok(Zero::zero())
+		}
+
+		Self::do_transfer(id, from, to, amount)?;
+
+		Ok(amount)
+	}
+
+	/// Run the checks of `transfer` without mutating state.
+	pub fn validate_transfer(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(who != recipient, Error::<T>::ConfuseBehavior);
+		Self::ensure_transfer_allowed(id, who, recipient)?;
+
+		Self::validate_move_balance(id, who, recipient, amount)
+	}
+
+	/// Check the token's `TransferPolicy` lets `sender` send to `recipient`, and that `sender`
+	/// is out of the token's transfer cooldown.
+	fn ensure_transfer_allowed(
+		id: T::FungibleTokenId,
+		sender: &T::AccountId,
+		recipient: &T::AccountId,
+	) -> DispatchResult {
+		let token = Tokens::<T>::get(id);
+		if let Some(cooldown) = token.as_ref().and_then(|token| token.transfer_cooldown) {
+			if let Some(last) = LastTransfer::<T>::get(id, sender) {
+				ensure!(
+					frame_system::Pallet::<T>::block_number() >= last.saturating_add(cooldown),
+					Error::<T>::TransferCooldown
+				);
+			}
+		}
+
+		let policy = token.map(|token| token.transfer_policy).unwrap_or_default();
+		let allowed = match policy {
+			TransferPolicy::Open => true,
+			TransferPolicy::Allowlist =>
+				ListedAccounts::<T>::get(id, sender) && ListedAccounts::<T>::get(id, recipient),
+			TransferPolicy::Blocklist =>
+				!ListedAccounts::<T>::get(id, sender) && !ListedAccounts::<T>::get(id, recipient),
+		};
+		ensure!(allowed, Error::<T>::TransferNotAllowed);
+		Ok(())
+	}
+
+	/// Weight of the reads `transfer_from` performs before any state is changed.
+	fn transfer_from_validation_weight() -> Weight {
+		// token existence, allowance, both listings and both balances
+		T::DbWeight::get().reads(6)
+	}
+
+	/// Run the checks of `transfer_from` without mutating state.
+	pub fn validate_transfer_from(
+		id: T::FungibleTokenId,
+		spender: &T::AccountId,
+		sender: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(spender != recipient, Error::<T>::ConfuseBehavior);
+
+		ensure!(
+			Allowances::<T>::get(id, (sender, spender)) >= amount,
+			Error::<T>::InsufficientAuthorizedTokens
+		);
+		Self::ensure_transfer_allowed(id, sender, recipient)?;
+
+		Self::validate_move_balance(id, sender, recipient, amount)
+	}
+
+	fn validate_move_balance(
+		id: T::FungibleTokenId,
+		sender: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		// The zero account stands for mints and burns in events, it never holds tokens.
+		let zero = Self::zero_account_id();
+		ensure!(sender != &zero && recipient != &zero, Error::<T>::InvalidAccount);
+
+		let sender_balance = Balances::<T>::get(id, sender);
+		ensure!(sender_balance >= amount, Error::<T>::InsufficientTokens);
+
+		// Make sure the recipient can be credited before the sender is debited, so the
+		// transfer is all-or-nothing regardless of what runs in between.
+		let recipient_balance = Balances::<T>::get(id, recipient);
+		ensure!(recipient_balance.checked_add(amount).is_some(), Error::<T>::NumOverflow);
+
+		// A sender giving up its whole balance frees the slot a new recipient takes.
+		if recipient_balance.is_zero() && !amount.is_zero() && sender_balance != amount {
+			Self::ensure_holder_capacity(id)?;
+		}
+
+		Ok(())
+	}
+
+	pub fn do_transfer_batch(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		recipients: Vec<(T::AccountId, Balance)>,
+		aggregate: bool,
+	) -> DispatchResult {
+		ensure!(
+			recipients.len() <= T::MaxBatchTransfers::get() as usize,
+			Error::<T>::TooManyTransfers
+		);
+
+		let mut total = Balance::zero();
+		for (recipient, amount) in recipients.iter() {
+			Self::validate_transfer(id, who, recipient, *amount)?;
+
+			if aggregate {
+				Self::settle_transfer(id, who, recipient, *amount)?;
+			} else {
+				Self::internal_transfer(id, who, recipient, *amount)?;
+			}
+			total = total.checked_add(*amount).ok_or(Error::<T>::NumOverflow)?;
+		}
+
+		if aggregate {
+			Self::deposit_event(Event::BatchTransfer(
+				id,
+				who.clone(),
+				recipients.len() as u32,
+				total,
+				Self::next_event_seq(),
+			));
+		}
+
+		Ok(())
+	}
+
+	pub fn do_transfer_split(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		recipients: Vec<(T::AccountId, Perbill)>,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(
+			recipients.len() <= T::MaxBatchTransfers::get() as usize,
+			Error::<T>::TooManyTransfers
+		);
+		let parts = recipients
+			.iter()
+			.fold(0u64, |parts, (_, fraction)| parts.saturating_add(fraction.deconstruct().into()));
+		ensure!(parts == Perbill::one().deconstruct() as u64, Error::<T>::InvalidSplit);
+
+		let mut left = amount;
+		let last = recipients.len() - 1;
+		for (index, (recipient, fraction)) in recipients.iter().enumerate() {
+			let share =
+				if index == last { left } else { Self::round_share(*fraction, amount).min(left) };
+			left = left.saturating_sub(share);
+			if !share.is_zero() {
+				Self::do_transfer(id, who, recipient, share)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn internal_transfer(
+		id: T::FungibleTokenId,
+		sender: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		let net = Self::settle_transfer(id, sender, recipient, amount)?;
+
+		if Tokens::<T>::get(id).map_or(true, |token| token.emit_transfer_events) {
+			Self::deposit_event(Event::Transfer(
+				id,
+				sender.clone(),
+				recipient.clone(),
+				net,
+				Self::next_event_seq(),
+			));
+		}
+
+		Ok(())
+	}
+
+	/// Everything a transfer of `amount` does but its event: enforce the transaction and wallet
+	/// limits, charge the transfer and reflection fees, move the rest to `recipient` and start
+	/// the sender's cooldown. Returns the amount `recipient` got.
+	fn settle_transfer(
+		id: T::FungibleTokenId,
+		sender: &T::AccountId,
+		recipient: &T::AccountId,
+		amount: Balance,
+	) -> Result<Balance, DispatchError> {
+		let max_tx_amount = Tokens::<T>::get(id).and_then(|token| token.max_tx_amount);
+		ensure!(max_tx_amount.map_or(true, |max| amount <= max), Error::<T>::ExceedsMaxTx);
+
+		let net = Self::charge_transfer_fee(id, sender, amount)?;
+		let net = Self::charge_reflection_fee(id, sender, net)?;
+		Self::ensure_within_max_wallet(id, recipient, net)?;
+
+		Self::move_balance(id, sender, recipient, net)?;
+		if Tokens::<T>::get(id).and_then(|token| token.transfer_cooldown).is_some() {
+			LastTransfer::<T>::insert(id, sender, frame_system::Pallet::<T>::block_number());
+		}
+
+		Ok(net)
+	}
+
+	/// The fee `sender` pays to the token owner when transferring `amount`. Transfers made
+	/// by the owner are exempt.
+	pub fn transfer_fee(id: T::FungibleTokenId, sender: &T::AccountId, amount: Balance) -> Balance {
+		let fee_bps = TransferFeeBps::<T>::get(id);
+		if fee_bps.is_zero() {
+			return Zero::zero()
+		}
+		match Tokens::<T>::get(id) {
+			Some(token) if token.owner != *sender => Self::round_share(
+				Perbill::from_rational(u32::from(fee_bps), u32::from(MAX_BPS)),
+				amount,
+			),
+			_ => Zero::zero(),
 		}
+	}
 
-		#[pallet::weight(T::WeightInfo::burn())]
-		pub fn burn(
-			origin: OriginFor<T>,
-			id: T::FungibleTokenId,
-			amount: Balance,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			Self::do_burn(id, &who, amount)
+	/// `fraction` of `amount`, rounded as `FeeRounding` says.
+	fn round_share(fraction: Perbill, amount: Balance) -> Balance {
+		match T::FeeRounding::get() {
+			RoundingMode::Floor => fraction.mul_floor(amount),
+			RoundingMode::Ceil => fraction.mul_ceil(amount),
+			RoundingMode::Nearest => fraction * amount,
 		}
 	}
-}
 
-impl<T: Config> Pallet<T> {
-	fn zero_account_id() -> T::AccountId {
-		T::AccountId::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed")
+	/// Move the transfer fee of `amount` from `sender` to the token owner, or to the fee split
+	/// recipients if there are any, and return what is left for the recipient.
+	fn charge_transfer_fee(
+		id: T::FungibleTokenId,
+		sender: &T::AccountId,
+		amount: Balance,
+	) -> Result<Balance, DispatchError> {
+		let fee = Self::transfer_fee(id, sender, amount);
+		let splits = FeeSplits::<T>::get(id);
+		if !fee.is_zero() && !splits.is_empty() {
+			// Every share but the last is rounded and the last takes what is left, so the shares
+			// always add up to the fee.
+			let mut remaining = fee;
+			let last = splits.len() - 1;
+			for (index, (account, bps)) in splits.into_iter().enumerate() {
+				let share = if index == last {
+					remaining
+				} else {
+					Self::round_share(
+						Perbill::from_rational(u32::from(bps), u32::from(MAX_BPS)),
+						amount,
+					)
+					.min(remaining)
+				};
+				remaining = remaining.saturating_sub(share);
+				if share.is_zero() {
+					continue
+				}
+				if account == Self::zero_account_id() {
+					Self::do_burn(id, sender, share)?;
+				} else {
+					Self::move_balance(id, sender, &account, share)?;
+					Self::deposit_event(Event::Transfer(
+						id,
+						sender.clone(),
+						account,
+						share,
+						Self::next_event_seq(),
+					));
+				}
+			}
+		} else if !fee.is_zero() {
+			let owner = Tokens::<T>::get(id).ok_or(Error::<T>::InvalidId)?.owner;
+			Self::move_balance(id, sender, &owner, fee)?;
+			Self::deposit_event(Event::Transfer(
+				id,
+				sender.clone(),
+				owner,
+				fee,
+				Self::next_event_seq(),
+			));
+		}
+
+		Ok(amount.saturating_sub(fee))
 	}
 
-	pub fn exists(id: T::FungibleTokenId) -> bool {
-		Tokens::<T>::contains_key(id)
+	/// The pallet's own account, holding reflections until their holders claim them.
+	pub fn reflection_account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
 	}
 
-	pub fn total_supply(id: T::FungibleTokenId) -> Balance {
-		Tokens::<T>::get(id).unwrap().total_supply
+	/// The account receiving mint fees.
+	pub fn treasury_account_id() -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(b"treasury")
 	}
 
-	pub fn do_create_token(
+	/// The account holding the unfilled sell side of limit orders.
+	pub fn order_escrow_account_id() -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(b"orders")
+	}
+
+	pub fn do_place_order(
 		who: &T::AccountId,
-		id: T::FungibleTokenId,
-		name: Vec<u8>,
-		symbol: Vec<u8>,
-		decimals: u8,
-	) -> DispatchResult {
-		let bounded_name: BoundedVec<u8, T::StringLimit> =
-			name.clone().try_into().map_err(|_| Error::<T>::BadMetadata)?;
-		let bounded_symbol: BoundedVec<u8, T::StringLimit> =
-			symbol.clone().try_into().map_err(|_| Error::<T>::BadMetadata)?;
-
-		let token = Token {
-			owner: who.clone(),
-			name: bounded_name,
-			symbol: bounded_symbol,
-			decimals,
-			total_supply: Balance::default(),
-		};
+		sell_id: T::FungibleTokenId,
+		sell_amount: Balance,
+		buy_id: T::FungibleTokenId,
+		buy_amount: Balance,
+	) -> Result<u64, DispatchError> {
+		ensure!(sell_id != buy_id, Error::<T>::InvalidOrder);
+		ensure!(!sell_amount.is_zero() && !buy_amount.is_zero(), Error::<T>::InvalidOrder);
+
+		let escrow = Self::order_escrow_account_id();
+		Self::validate_move_balance(sell_id, who, &escrow, sell_amount)?;
+		Self::move_balance(sell_id, who, &escrow, sell_amount)?;
+
+		let order_id = NextOrderId::<T>::get();
+		NextOrderId::<T>::put(order_id.checked_add(1).ok_or(Error::<T>::NumOverflow)?);
+		Orders::<T>::insert(
+			order_id,
+			LimitOrder {
+				maker: who.clone(),
+				sell_id,
+				sell_amount,
+				buy_id,
+				buy_amount,
+				remaining: sell_amount,
+			},
+		);
 
-		Tokens::<T>::insert(id, token);
+		Self::deposit_event(Event::OrderPlaced(
+			order_id,
+			who.clone(),
+			sell_id,
+			sell_amount,
+			buy_id,
+			buy_amount,
+		));
 
-		Self::deposit_event(Event::TokenCreated(id, who.clone(), name, symbol, decimals));
+		Ok(order_id)
+	}
 
-		Ok(())
+	/// What a taker pays for `fill_amount` of `order`'s sell side, rounded up.
+	pub fn order_fill_price(
+		order: &LimitOrder<T::AccountId, T::FungibleTokenId>,
+		fill_amount: Balance,
+	) -> Balance {
+		let numerator = U256::from(fill_amount) * U256::from(order.buy_amount);
+		let sell_amount = U256::from(order.sell_amount);
+		let price = (numerator + sell_amount - 1) / sell_amount;
+		// Never more than `buy_amount` while `fill_amount` is at most `sell_amount`.
+		price.try_into().unwrap_or(Balance::MAX)
 	}
 
-	pub fn do_approve(
-		id: T::FungibleTokenId,
+	pub fn do_fill_order(
 		who: &T::AccountId,
-		spender: &T::AccountId,
-		amount: Balance,
+		order_id: u64,
+		fill_amount: Balance,
 	) -> DispatchResult {
-		ensure!(spender != who, Error::<T>::ApproveToCurrentOwner);
+		let mut order = Orders::<T>::get(order_id).ok_or(Error::<T>::UnknownOrder)?;
+		ensure!(who != &order.maker, Error::<T>::ConfuseBehavior);
+		ensure!(!fill_amount.is_zero(), Error::<T>::InvalidOrder);
+		ensure!(fill_amount <= order.remaining, Error::<T>::OrderOverfilled);
+
+		let price = Self::order_fill_price(&order, fill_amount);
+		// Escrow only holds the tokens, the trade is between maker and taker.
+		Self::ensure_transfer_allowed(order.buy_id, who, &order.maker)?;
+		Self::ensure_transfer_allowed(order.sell_id, &order.maker, who)?;
+		let escrow = Self::order_escrow_account_id();
+		Self::validate_move_balance(order.buy_id, who, &order.maker, price)?;
+		Self::validate_move_balance(order.sell_id, &escrow, who, fill_amount)?;
+
+		Self::internal_transfer(order.buy_id, who, &order.maker, price)?;
+		// The escrowed tokens already paid their way in, the taker gets all of them.
+		Self::move_balance(order.sell_id, &escrow, who, fill_amount)?;
+
+		order.remaining = order.remaining.saturating_sub(fill_amount);
+		if order.remaining.is_zero() {
+			Orders::<T>::remove(order_id);
+		} else {
+			Orders::<T>::insert(order_id, order);
+		}
 
-		ensure!(
-			Balances::<T>::get(id, who.clone()) >= amount,
-			Error::<T>::InsufficientAuthorizedTokens
-		);
+		Self::deposit_event(Event::OrderFilled(order_id, who.clone(), fill_amount, price));
 
-		Allowances::<T>::try_mutate(id, (&who, &spender), |allowance| -> DispatchResult {
-			*allowance = allowance.checked_add(amount).ok_or(Error::<T>::NumOverflow)?;
-			Ok(())
-		})?;
+		Ok(())
+	}
 
-		Self::deposit_event(Event::Transfer(id, who.clone(), spender.clone(), amount));
+	pub fn do_cancel_order(who: &T::AccountId, order_id: u64) -> DispatchResult {
+		let order = Orders::<T>::get(order_id).ok_or(Error::<T>::UnknownOrder)?;
+		ensure!(who == &order.maker, Error::<T>::NoPermission);
+
+		Self::move_balance(order.sell_id, &Self::order_escrow_account_id(), who, order.remaining)?;
+		Orders::<T>::remove(order_id);
+
+		Self::deposit_event(Event::OrderCancelled(order_id, order.remaining));
 
 		Ok(())
 	}
 
-	pub fn do_transfer_from(
-		id: T::FungibleTokenId,
-		who: T::AccountId,
-		sender: T::AccountId,
-		recipient: T::AccountId,
-		amount: Balance,
-	) -> DispatchResult {
-		ensure!(who != recipient, Error::<T>::ConfuseBehavior);
+	/// The account holding what payment streams have not paid out yet.
+	pub fn stream_escrow_account_id() -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(b"streams")
+	}
 
-		ensure!(
-			Allowances::<T>::get(id, (&sender, &who)) >= amount,
-			Error::<T>::InsufficientAuthorizedTokens
+	pub fn do_create_stream(
+		who: &T::AccountId,
+		id: T::FungibleTokenId,
+		recipient: &T::AccountId,
+		total: Balance,
+		start: T::BlockNumber,
+		end: T::BlockNumber,
+	) -> Result<u64, DispatchError> {
+		ensure!(who != recipient, Error::<T>::InvalidStream);
+		ensure!(!total.is_zero() && start < end, Error::<T>::InvalidStream);
+		Self::ensure_transfer_allowed(id, who, recipient)?;
+
+		let escrow = Self::stream_escrow_account_id();
+		Self::validate_move_balance(id, who, &escrow, total)?;
+		Self::move_balance(id, who, &escrow, total)?;
+
+		let stream_id = NextStreamId::<T>::get();
+		NextStreamId::<T>::put(stream_id.checked_add(1).ok_or(Error::<T>::NumOverflow)?);
+		Streams::<T>::insert(
+			stream_id,
+			Stream {
+				id,
+				sender: who.clone(),
+				recipient: recipient.clone(),
+				total,
+				withdrawn: Zero::zero(),
+				start,
+				end,
+			},
 		);
 
-		Allowances::<T>::try_mutate(id, (&sender, &who), |allowance| -> DispatchResult {
-			*allowance = allowance.checked_sub(amount).ok_or(Error::<T>::NumOverflow)?;
-			Ok(())
-		})?;
+		Self::deposit_event(Event::StreamCreated(
+			stream_id,
+			id,
+			who.clone(),
+			recipient.clone(),
+			total,
+			start,
+			end,
+		));
+
+		Ok(stream_id)
+	}
 
-		Self::internal_transfer(id, &sender, &recipient, amount)?;
+	/// How much of `stream` has vested by `now`, rounded down, including what was already
+	/// withdrawn.
+	pub fn stream_vested(
+		stream: &Stream<T::AccountId, T::FungibleTokenId, T::BlockNumber>,
+		now: T::BlockNumber,
+	) -> Balance {
+		if now <= stream.start {
+			return Zero::zero()
+		}
+		if now >= stream.end {
+			return stream.total
+		}
+		let elapsed: u128 = (now - stream.start).saturated_into();
+		let duration: u128 = (stream.end - stream.start).saturated_into();
+		let vested = U256::from(stream.total) * U256::from(elapsed) / U256::from(duration);
+		// Less than `total` while `elapsed` is less than `duration`.
+		vested.try_into().unwrap_or(stream.total)
+	}
+
+	pub fn do_withdraw_from_stream(who: &T::AccountId, stream_id: u64) -> DispatchResult {
+		let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStream)?;
+		ensure!(who == &stream.recipient, Error::<T>::NoPermission);
+
+		let vested = Self::stream_vested(&stream, frame_system::Pallet::<T>::block_number());
+		let amount = vested.saturating_sub(stream.withdrawn);
+		ensure!(!amount.is_zero(), Error::<T>::NothingToWithdraw);
+
+		Self::move_balance(stream.id, &Self::stream_escrow_account_id(), who, amount)?;
+
+		stream.withdrawn = vested;
+		if stream.withdrawn == stream.total {
+			Streams::<T>::remove(stream_id);
+		} else {
+			Streams::<T>::insert(stream_id, stream);
+		}
+
+		Self::deposit_event(Event::StreamWithdrawn(stream_id, amount));
 
 		Ok(())
 	}
 
-	pub fn do_transfer(
+	pub fn do_cancel_stream(who: &T::AccountId, stream_id: u64) -> DispatchResult {
+		let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStream)?;
+		ensure!(who == &stream.sender, Error::<T>::NoPermission);
+
+		let vested = Self::stream_vested(&stream, frame_system::Pallet::<T>::block_number());
+		let owed = vested.saturating_sub(stream.withdrawn);
+		let returned = stream.total.saturating_sub(vested);
+
+		let escrow = Self::stream_escrow_account_id();
+		Self::move_balance(stream.id, &escrow, &stream.recipient, owed)?;
+		Self::move_balance(stream.id, &escrow, who, returned)?;
+		Streams::<T>::remove(stream_id);
+
+		Self::deposit_event(Event::StreamCancelled(stream_id, owed, returned));
+
+		Ok(())
+	}
+
+	/// Pay `MintFee` for each of `count` credited accounts from `who` to the treasury account,
+	/// failing if `who` can't afford it.
+	fn charge_mint_fee(who: &T::AccountId, count: u32) -> DispatchResult {
+		let fee = T::MintFee::get().saturating_mul(count.into());
+		if fee.is_zero() {
+			return Ok(())
+		}
+
+		T::Currency::transfer(
+			who,
+			&Self::treasury_account_id(),
+			fee,
+			ExistenceRequirement::KeepAlive,
+		)
+	}
+
+	/// The reflection fee on `amount`, what a transfer has left after its transfer fee, or
+	/// nothing when no one would be credited with it.
+	pub fn reflection_fee(id: T::FungibleTokenId, amount: Balance) -> Balance {
+		let fee_bps = ReflectionFeeBps::<T>::get(id);
+		if fee_bps.is_zero() {
+			return Zero::zero()
+		}
+		let fee = Self::round_share(
+			Perbill::from_rational(u32::from(fee_bps), u32::from(MAX_BPS)),
+			amount,
+		);
+
+		if Self::reflection_eligible_supply(id).saturating_sub(fee).is_zero() {
+			return Zero::zero()
+		}
+		fee
+	}
+
+	/// Move the reflection fee of `amount` from `sender` to the reflection account, credit it
+	/// to every holder in proportion to their balance and return what is left for the
+	/// recipient. Nothing is charged when no one would be credited.
+	fn charge_reflection_fee(
 		id: T::FungibleTokenId,
-		who: &T::AccountId,
-		recipient: &T::AccountId,
+		sender: &T::AccountId,
 		amount: Balance,
-	) -> DispatchResult {
-		ensure!(who != recipient, Error::<T>::ConfuseBehavior);
+	) -> Result<Balance, DispatchError> {
+		let fee = Self::reflection_fee(id, amount);
+		if fee.is_zero() {
+			return Ok(amount)
+		}
 
-		ensure!(Balances::<T>::get(id, who.clone()) >= amount, Error::<T>::InsufficientTokens);
+		let pool = Self::reflection_account_id();
+		let eligible = Self::reflection_eligible_supply(id).saturating_sub(fee);
+
+		Self::move_balance(id, sender, &pool, fee)?;
+		UnclaimedReflections::<T>::mutate(id, |unclaimed| {
+			*unclaimed = unclaimed.saturating_add(fee)
+		});
+		MagnifiedPerShare::<T>::mutate(id, |per_share| {
+			*per_share = per_share.saturating_add(
+				U256::from(fee).saturating_mul(REFLECTION_MAGNITUDE.into()) / U256::from(eligible),
+			)
+		});
+		Self::deposit_event(Event::Transfer(id, sender.clone(), pool, fee, Self::next_event_seq()));
+
+		Ok(amount.saturating_sub(fee))
+	}
 
-		Self::internal_transfer(id, who, recipient, amount)?;
+	/// The supply reflections are shared over: everything but what the pallet's own accounts
+	/// hold, as those never accrue any.
+	fn reflection_eligible_supply(id: T::FungibleTokenId) -> Balance {
+		[
+			Self::reflection_account_id(),
+			Self::treasury_account_id(),
+			Self::order_escrow_account_id(),
+			Self::stream_escrow_account_id(),
+		]
+		.iter()
+		.fold(Self::total_supply(id), |eligible, account| {
+			eligible.saturating_sub(Balances::<T>::get(id, account))
+		})
+	}
+
+	/// Reflections accrued by `who` on its current balance since it was last settled.
+	fn unsettled_reflections(id: T::FungibleTokenId, who: &T::AccountId) -> Balance {
+		if Self::is_pallet_account(who) {
+			return Zero::zero()
+		}
+		let per_share = MagnifiedPerShare::<T>::get(id);
+		let owed = per_share
+			.saturating_sub(ReflectionCheckpoints::<T>::get(id, who))
+			.saturating_mul(Balances::<T>::get(id, who).into()) /
+			U256::from(REFLECTION_MAGNITUDE);
+		owed.try_into().unwrap_or(Balance::MAX)
+	}
+
+	/// Bank the unsettled reflections of `who`. Must run before every change to its balance.
+	fn settle_reflections(id: T::FungibleTokenId, who: &T::AccountId) {
+		let per_share = MagnifiedPerShare::<T>::get(id);
+		if per_share.is_zero() || Self::is_pallet_account(who) {
+			return
+		}
+
+		let owed = Self::unsettled_reflections(id, who);
+		if !owed.is_zero() {
+			PendingReflections::<T>::mutate(id, who, |pending| {
+				*pending = pending.saturating_add(owed)
+			});
+		}
+		ReflectionCheckpoints::<T>::insert(id, who, per_share);
+	}
+
+	/// Reflections `who` could claim on a token right now.
+	pub fn claimable_reflections(id: T::FungibleTokenId, who: &T::AccountId) -> Balance {
+		PendingReflections::<T>::get(id, who).saturating_add(Self::unsettled_reflections(id, who))
+	}
+
+	pub fn do_claim_reflections(id: T::FungibleTokenId, who: &T::AccountId) -> DispatchResult {
+		Self::settle_reflections(id, who);
+		let amount = PendingReflections::<T>::take(id, who);
+		if amount.is_zero() {
+			return Ok(())
+		}
+
+		Self::move_balance(id, &Self::reflection_account_id(), who, amount)?;
+		UnclaimedReflections::<T>::mutate(id, |unclaimed| {
+			*unclaimed = unclaimed.saturating_sub(amount)
+		});
+
+		Self::deposit_event(Event::ReflectionsClaimed(id, who.clone(), amount));
 
 		Ok(())
 	}
 
-	fn internal_transfer(
+	fn move_balance(
 		id: T::FungibleTokenId,
 		sender: &T::AccountId,
 		recipient: &T::AccountId,
@@ -342,8 +3263,7 @@ impl<T: Config> Pallet<T> {
 	) -> DispatchResult {
 		Self::decrease_balance(id, sender, amount)?;
 		Self::increase_balance(id, recipient, amount)?;
-
-		Self::deposit_event(Event::Transfer(id, sender.clone(), recipient.clone(), amount));
+		Self::touch(id);
 
 		Ok(())
 	}
@@ -361,43 +3281,264 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	pub fn do_bridge_mint(
+		id: T::FungibleTokenId,
+		to: &T::AccountId,
+		amount: Balance,
+		external_ref: H256,
+	) -> DispatchResult {
+		ensure!(!ProcessedBridgeMints::<T>::get(id, external_ref), Error::<T>::AlreadyProcessed);
+
+		Self::internal_mint(id, to, amount)?;
+		ProcessedBridgeMints::<T>::insert(id, external_ref, true);
+
+		Self::deposit_event(Event::BridgeMinted(id, to.clone(), amount, external_ref));
+
+		Ok(())
+	}
+
+	pub fn do_mint_batch(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		mints: Vec<(T::AccountId, Balance)>,
+	) -> DispatchResult {
+		ensure!(mints.len() <= T::MaxBatchMints::get() as usize, Error::<T>::TooManyMints);
+		Self::maybe_check_permission(id, who)?;
+
+		// Fail before crediting anyone rather than saturate the supply.
+		mints
+			.iter()
+			.try_fold(Self::total_supply(id), |total, (_, amount)| total.checked_add(*amount))
+			.ok_or(Error::<T>::NumOverflow)?;
+
+		for (account, amount) in mints.iter() {
+			Self::internal_mint(id, account, *amount)?;
+		}
+
+		Ok(())
+	}
+
 	fn internal_mint(
 		id: T::FungibleTokenId,
 		account: &T::AccountId,
 		amount: Balance,
 	) -> DispatchResult {
-		Tokens::<T>::try_mutate_exists(id, |maybe_token| -> DispatchResult {
-			let token = maybe_token.as_mut().ok_or(Error::<T>::Unknown)?;
+		ensure!(account != &Self::zero_account_id(), Error::<T>::InvalidAccount);
 
-			Self::increase_balance(id, account, amount)?;
+		let token = Tokens::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+		if token.whole_units_only {
+			let whole = Self::scale_amount(1, token.decimals)
+				.map_or(amount.is_zero(), |unit| (amount % unit).is_zero());
+			ensure!(whole, Error::<T>::FractionalMintNotAllowed);
+		}
 
-			let new_total_supply = token.total_supply.saturating_add(amount);
-			token.total_supply = new_total_supply;
-			Ok(())
-		})?;
+		let supply_before = token.total_supply;
+		let expected_supply = supply_before.checked_add(amount).ok_or(Error::<T>::NumOverflow)?;
+		ensure!(
+			token.max_supply.map_or(true, |cap| expected_supply <= cap),
+			Error::<T>::SupplyCapExceeded
+		);
+		Self::ensure_within_max_wallet(id, account, amount)?;
+
+		Self::increase_balance(id, account, amount)?;
+		let new_total_supply =
+			Self::update_total_supply(id, |supply| supply.saturating_add(amount))?;
+		debug_assert_eq!(new_total_supply, expected_supply, "mint must add exactly `amount`");
+
+		MintCount::<T>::mutate(|count| *count = count.saturating_add(1));
+		TotalMinted::<T>::mutate(id, |total| *total = total.saturating_add(amount));
 
-		Self::deposit_event(Event::Mint(id, Self::zero_account_id(), account.clone(), amount));
+		Self::deposit_event(Event::Mint(
+			id,
+			Self::zero_account_id(),
+			account.clone(),
+			amount,
+			Self::next_event_seq(),
+		));
+		Self::check_supply_threshold(id, new_total_supply);
 
 		Ok(())
 	}
 
+	/// Mint the emission of every scheduled token, capped at its `max_supply`. Schedules
+	/// whose cap is reached are removed, and mints the token refuses are skipped for this
+	/// block.
+	fn process_emissions() -> Weight {
+		let emissions: Vec<_> = Emissions::<T>::iter().collect();
+		let mut weight = T::DbWeight::get().reads(emissions.len().saturating_add(1) as Weight);
+
+		for (id, (recipient, per_block)) in emissions {
+			let amount = match Tokens::<T>::get(id) {
+				Some(token) => token
+					.max_supply
+					.map_or(per_block, |cap| cap.saturating_sub(token.total_supply).min(per_block)),
+				None => Zero::zero(),
+			};
+			if amount.is_zero() {
+				Emissions::<T>::remove(id);
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+				continue
+			}
+
+			let _ = with_storage_layer(|| Self::internal_mint(id, &recipient, amount));
+			weight = weight.saturating_add(T::WeightInfo::mint());
+		}
+
+		weight
+	}
+
+	/// Draw `winners_count` distinct holders other than the owner, each with a chance
+	/// proportional to its balance among those not drawn yet, and pay each
+	/// `prize_amount / winners_count` from the owner. The rest of the division stays with the
+	/// owner. Holders are ordered by account before drawing so the outcome only depends on the
+	/// randomness. Pallet accounts never win, and tokens with more than `MaxLotteryHolders`
+	/// balance entries, emptied ones included, can't be drawn from.
+	pub fn do_lottery_distribute(
+		who: &T::AccountId,
+		id: T::FungibleTokenId,
+		prize_amount: Balance,
+		winners_count: u32,
+	) -> Result<Vec<T::AccountId>, DispatchError> {
+		ensure!(Self::exists(id), Error::<T>::InvalidId);
+		Self::maybe_check_permission(id, who)?;
+
+		// Bound the entries read, not the holders kept, or emptied balances would be walked for
+		// free.
+		let max_holders = T::MaxLotteryHolders::get() as usize;
+		let entries: Vec<(T::AccountId, Balance)> =
+			Balances::<T>::iter_prefix(id).take(max_holders.saturating_add(1)).collect();
+		ensure!(entries.len() <= max_holders, Error::<T>::TooManyHolders);
+		let mut holders: Vec<(T::AccountId, Balance)> = entries
+			.into_iter()
+			.filter(|(account, balance)| {
+				account != who && !balance.is_zero() && !Self::is_pallet_account(account)
+			})
+			.collect();
+		ensure!(
+			winners_count > 0 && winners_count as usize <= holders.len(),
+			Error::<T>::InvalidLottery
+		);
+		let share = prize_amount / Balance::from(winners_count);
+		ensure!(!share.is_zero(), Error::<T>::InvalidLottery);
+		holders.sort_by(|a, b| a.0.cmp(&b.0));
+
+		let mut weight = holders
+			.iter()
+			.fold(0, |acc: Balance, (_, balance)| acc.saturating_add(*balance));
+		let mut winners = Vec::with_capacity(winners_count as usize);
+		for draw in 0..winners_count {
+			let (seed, _) = T::Randomness::random(&(LOTTERY_SUBJECT, id, draw).encode());
+			let mut target = (U256::from_big_endian(seed.as_ref()) % U256::from(weight)).low_u128();
+			let index = holders
+				.iter()
+				.position(|(_, balance)| {
+					if target < *balance {
+						true
+					} else {
+						target -= balance;
+						false
+					}
+				})
+				.ok_or(Error::<T>::Unknown)?;
+			let (winner, balance) = holders.remove(index);
+			weight = weight.saturating_sub(balance);
+			winners.push(winner);
+		}
+
+		for winner in &winners {
+			Self::do_transfer(id, who, winner, share)?;
+		}
+		Self::deposit_event(Event::LotteryDistributed(id, winners.clone(), share));
+
+		Ok(winners)
+	}
+
+	/// Deposit `SupplyThresholdReached` once `total_supply` reaches the armed threshold.
+	fn check_supply_threshold(id: T::FungibleTokenId, total_supply: Balance) {
+		if let Some(threshold) = SupplyThresholds::<T>::get(id) {
+			if total_supply >= threshold && !SupplyThresholdReached::<T>::get(id) {
+				SupplyThresholdReached::<T>::insert(id, true);
+				Self::deposit_event(Event::SupplyThresholdReached(id, threshold));
+			}
+		}
+	}
+
 	pub fn do_burn(
 		id: T::FungibleTokenId,
 		account: &T::AccountId,
 		amount: Balance,
 	) -> DispatchResult {
-		Tokens::<T>::try_mutate_exists(id, |maybe_token| -> DispatchResult {
-			let token = maybe_token.as_mut().ok_or(Error::<T>::Unknown)?;
+		ensure!(account != &Self::zero_account_id(), Error::<T>::InvalidAccount);
+
+		let supply_before = Tokens::<T>::get(id).ok_or(Error::<T>::Unknown)?.total_supply;
+
+		Self::decrease_balance(id, account, amount)?;
+		let new_total_supply =
+			Self::update_total_supply(id, |supply| supply.saturating_sub(amount))?;
+		// The balance held `amount`, so the supply did too.
+		debug_assert_eq!(
+			new_total_supply,
+			supply_before - amount,
+			"burn must remove exactly `amount`"
+		);
 
-			Self::decrease_balance(id, account, amount)?;
+		BurnCount::<T>::mutate(|count| *count = count.saturating_add(1));
+		TotalBurned::<T>::mutate(id, |total| *total = total.saturating_add(amount));
 
-			let new_total_supply = token.total_supply.saturating_sub(amount);
-			token.total_supply = new_total_supply;
-			Ok(())
-		})?;
+		Self::deposit_event(Event::Transfer(
+			id,
+			account.clone(),
+			Self::zero_account_id(),
+			amount,
+			Self::next_event_seq(),
+		));
+
+		Ok(())
+	}
 
-		Self::deposit_event(Event::Transfer(id, account.clone(), Self::zero_account_id(), amount));
+	/// Apply `f` to the total supply of a token, returning the new supply. Callers move the
+	/// balance first and only then write the token, so nothing the balance update does to the
+	/// token can be overwritten by a stale copy of it.
+	fn update_total_supply(
+		id: T::FungibleTokenId,
+		f: impl FnOnce(Balance) -> Balance,
+	) -> Result<Balance, DispatchError> {
+		Tokens::<T>::try_mutate(id, |maybe_token| {
+			let token = maybe_token.as_mut().ok_or(Error::<T>::Unknown)?;
+			token.total_supply = f(token.total_supply);
+			token.last_activity = frame_system::Pallet::<T>::block_number();
+			Ok(token.total_supply)
+		})
+	}
 
+	/// Zero allowances are removed rather than stored, reads still return zero. Fails if this
+	/// would give `owner` more than `MaxApprovalsPerOwner` spenders.
+	fn set_allowance(
+		id: T::FungibleTokenId,
+		owner: &T::AccountId,
+		spender: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		let live = Allowances::<T>::contains_key(id, (owner, spender));
+		if amount.is_zero() {
+			Allowances::<T>::remove(id, (owner, spender));
+			SingleUseApprovals::<T>::remove(id, (owner, spender));
+			if live {
+				ApprovalCounts::<T>::mutate(id, owner, |count| *count = count.saturating_sub(1));
+			}
+		} else {
+			if !live {
+				ApprovalCounts::<T>::try_mutate(id, owner, |count| -> DispatchResult {
+					ensure!(
+						T::MaxApprovalsPerOwner::get().map_or(true, |max| *count < max),
+						Error::<T>::TooManyApprovals
+					);
+					*count = count.saturating_add(1);
+					Ok(())
+				})?;
+			}
+			Allowances::<T>::insert(id, (owner, spender), amount);
+		}
 		Ok(())
 	}
 
@@ -406,10 +3547,25 @@ impl<T: Config> Pallet<T> {
 		to: &T::AccountId,
 		amount: Balance,
 	) -> DispatchResult {
-		Balances::<T>::try_mutate(id, to, |balance| -> DispatchResult {
-			*balance = balance.checked_add(amount).ok_or(Error::<T>::NumOverflow)?;
-			Ok(())
-		})?;
+		Self::settle_reflections(id, to);
+		let (before, after) =
+			Balances::<T>::try_mutate(id, to, |balance| -> Result<_, DispatchError> {
+				let before = *balance;
+				*balance = balance.checked_add(amount).ok_or(Error::<T>::NumOverflow)?;
+				if before.is_zero() && !balance.is_zero() {
+					Self::ensure_holder_capacity(id)?;
+					HolderCount::<T>::mutate(id, |count| *count = count.saturating_add(1));
+				}
+				Ok((before, *balance))
+			})?;
+		if before.is_zero() && !after.is_zero() && !frame_system::Pallet::<T>::account_exists(to) {
+			frame_system::Pallet::<T>::inc_providers(to);
+			ProvidedAccounts::<T>::insert(id, to, true);
+		}
+		if !amount.is_zero() && !EverHeld::<T>::get(id, to) {
+			EverHeld::<T>::insert(id, to, true);
+		}
+		Self::deposit_balance_changed(id, to, before, after);
 
 		Ok(())
 	}
@@ -419,17 +3575,99 @@ impl<T: Config> Pallet<T> {
 		from: &T::AccountId,
 		amount: Balance,
 	) -> DispatchResult {
-		Balances::<T>::try_mutate(id, from, |balance| -> DispatchResult {
-			*balance = balance.checked_sub(amount).ok_or(Error::<T>::NumOverflow)?;
-			Ok(())
-		})?;
+		Self::settle_reflections(id, from);
+		let (before, after) =
+			Balances::<T>::try_mutate(id, from, |balance| -> Result<_, DispatchError> {
+				let before = *balance;
+				*balance = balance.checked_sub(amount).ok_or(Error::<T>::NumOverflow)?;
+				Ok((before, *balance))
+			})?;
+		if !before.is_zero() && after.is_zero() {
+			HolderCount::<T>::mutate(id, |count| *count = count.saturating_sub(1));
+			if ProvidedAccounts::<T>::take(id, from) {
+				frame_system::Pallet::<T>::dec_providers(from)?;
+			}
+		}
+		Self::deposit_balance_changed(id, from, before, after);
+
+		Ok(())
+	}
+
+	/// Check `recipient` is none of the accounts `safe_transfer` refuses to send to.
+	pub fn ensure_safe_recipient(recipient: &T::AccountId) -> DispatchResult {
+		ensure!(recipient != &Self::zero_account_id(), Error::<T>::InvalidAccount);
+		ensure!(!Self::is_pallet_account(recipient), Error::<T>::PalletAccountRecipient);
+		ensure!(!T::UnsafeRecipients::contains(recipient), Error::<T>::UnsafeRecipient);
+		Ok(())
+	}
+
+	/// Whether `who` is one of the accounts the pallet holds tokens in.
+	fn is_pallet_account(who: &T::AccountId) -> bool {
+		who == &Self::reflection_account_id() ||
+			who == &Self::treasury_account_id() ||
+			who == &Self::order_escrow_account_id() ||
+			who == &Self::stream_escrow_account_id()
+	}
+
+	/// Check crediting `amount` to `who` keeps it within the token's `max_wallet`. The owner,
+	/// exempt accounts and the pallet's own accounts may hold any amount.
+	fn ensure_within_max_wallet(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		let token = match Tokens::<T>::get(id) {
+			Some(token) => token,
+			None => return Ok(()),
+		};
+		if let Some(max_wallet) = token.max_wallet {
+			if token.owner != *who &&
+				!MaxWalletExempt::<T>::get(id, who) &&
+				!Self::is_pallet_account(who)
+			{
+				let balance = Balances::<T>::get(id, who).saturating_add(amount);
+				ensure!(balance <= max_wallet, Error::<T>::ExceedsMaxWallet);
+			}
+		}
+		Ok(())
+	}
 
+	/// Reject memo-less transfers of tokens with `require_memo` set.
+	fn ensure_memo_not_required(id: T::FungibleTokenId) -> DispatchResult {
+		let require_memo = Tokens::<T>::get(id).map(|token| token.require_memo).unwrap_or_default();
+		ensure!(!require_memo, Error::<T>::MemoRequired);
 		Ok(())
 	}
 
+	/// Check the token's `max_holders` leaves room for one more holder.
+	fn ensure_holder_capacity(id: T::FungibleTokenId) -> DispatchResult {
+		if let Some(max_holders) = Tokens::<T>::get(id).and_then(|token| token.max_holders) {
+			ensure!(HolderCount::<T>::get(id) < max_holders, Error::<T>::HolderLimitExceeded);
+		}
+		Ok(())
+	}
+
+	/// Deposit `BalanceChanged` if enabled, then tell `OnBalanceChanged`, which may re-enter.
+	fn deposit_balance_changed(
+		id: T::FungibleTokenId,
+		who: &T::AccountId,
+		before: Balance,
+		after: Balance,
+	) {
+		if before == after {
+			return
+		}
+		if T::EmitBalanceChanged::get() {
+			Self::deposit_event(Event::BalanceChanged(id, who.clone(), before, after));
+		}
+		T::OnBalanceChanged::on_balance_changed(id, who, before, after);
+	}
+
+	/// Passing this counts as an owner action, restarting the fallback owner's wait.
 	fn maybe_check_permission(id: T::FungibleTokenId, who: &T::AccountId) -> DispatchResult {
 		let token = Tokens::<T>::get(id);
 		ensure!(*who == token.unwrap().owner, Error::<T>::NoPermission);
+		LastOwnerAction::<T>::insert(id, frame_system::Pallet::<T>::block_number());
 
 		Ok(())
 	}
@@ -450,3 +3688,117 @@ impl<T: Config> FungibleMetadata for Pallet<T> {
 		Tokens::<T>::get(id).unwrap().decimals
 	}
 }
+
+impl<T: Config> fungibles::Inspect<T::AccountId> for Pallet<T> {
+	type AssetId = T::FungibleTokenId;
+	type Balance = Balance;
+
+	fn total_issuance(asset: Self::AssetId) -> Self::Balance {
+		Tokens::<T>::get(asset).map(|token| token.total_supply).unwrap_or_default()
+	}
+
+	fn minimum_balance(_asset: Self::AssetId) -> Self::Balance {
+		Zero::zero()
+	}
+
+	fn balance(asset: Self::AssetId, who: &T::AccountId) -> Self::Balance {
+		Balances::<T>::get(asset, who)
+	}
+
+	fn reducible_balance(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		_keep_alive: bool,
+	) -> Self::Balance {
+		Pallet::<T>::spendable_balance(asset, who)
+	}
+
+	fn can_deposit(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		mint: bool,
+	) -> DepositConsequence {
+		let token = match Tokens::<T>::get(asset) {
+			Some(token) => token,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if mint && token.total_supply.checked_add(amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		if Balances::<T>::get(asset, who).checked_add(amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		DepositConsequence::Success
+	}
+
+	fn can_withdraw(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		let token = match Tokens::<T>::get(asset) {
+			Some(token) => token,
+			None => return WithdrawConsequence::UnknownAsset,
+		};
+		if token.total_supply.checked_sub(amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+		if Balances::<T>::get(asset, who) < amount {
+			return WithdrawConsequence::NoFunds
+		}
+		WithdrawConsequence::Success
+	}
+}
+
+/// `set_balance` only moves the balance, the supply is left to `set_total_issuance`. The
+/// blanket `Balanced` impl relies on this: it settles the supply once the imbalance of a
+/// `deposit` or `withdraw` is dropped.
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Pallet<T> {
+	fn set_balance(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		ensure!(Self::exists(asset), Error::<T>::InvalidId);
+		let current = Balances::<T>::get(asset, who);
+		if amount >= current {
+			let delta = amount - current;
+			Self::ensure_within_max_wallet(asset, who, delta)?;
+			Self::increase_balance(asset, who, delta)
+		} else {
+			Self::decrease_balance(asset, who, current - amount)
+		}
+	}
+
+	/// The change is counted in `TotalMinted` or `TotalBurned`, as a mint or burn of the
+	/// difference would be.
+	fn set_total_issuance(asset: Self::AssetId, amount: Self::Balance) {
+		Tokens::<T>::mutate(asset, |maybe_token| {
+			if let Some(token) = maybe_token {
+				if amount >= token.total_supply {
+					let minted = amount - token.total_supply;
+					TotalMinted::<T>::mutate(asset, |total| *total = total.saturating_add(minted));
+				} else {
+					let burned = token.total_supply - amount;
+					TotalBurned::<T>::mutate(asset, |total| *total = total.saturating_add(burned));
+				}
+				token.total_supply = amount;
+			}
+		});
+	}
+}
+
+impl<T: Config> fungibles::metadata::Inspect<T::AccountId> for Pallet<T> {
+	fn name(asset: &Self::AssetId) -> Vec<u8> {
+		Tokens::<T>::get(asset).map(|token| token.name.to_vec()).unwrap_or_default()
+	}
+
+	fn symbol(asset: &Self::AssetId) -> Vec<u8> {
+		Tokens::<T>::get(asset).map(|token| token.symbol.to_vec()).unwrap_or_default()
+	}
+
+	fn decimals(asset: &Self::AssetId) -> u8 {
+		Tokens::<T>::get(asset).map(|token| token.decimals).unwrap_or_default()
+	}
+}
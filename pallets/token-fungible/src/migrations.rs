@@ -0,0 +1,833 @@
+// This file is part of Web3Games.
+
+// Copyright (C) 2021-2022 Web3Games https://web3games.org
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	traits::{OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+pub mod v1 {
+	use super::*;
+
+	/// `Token` as stored before `created_at` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+	}
+
+	/// Backfill `created_at` of existing tokens with the block of the upgrade.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>>, _>(
+				|_, old| {
+					translated += 1;
+					Some(v2::OldToken {
+						owner: old.owner,
+						name: old.name,
+						symbol: old.symbol,
+						decimals: old.decimals,
+						total_supply: old.total_supply,
+						created_at: now,
+					})
+				},
+			);
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(2), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// `Token` as stored before `last_activity` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+	}
+
+	/// Start the inactivity period of existing tokens at the block of the upgrade.
+	pub struct MigrateToV2<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v3::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: now,
+				})
+			});
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(2), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// `Token` as stored before `max_allowance` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+	}
+
+	/// Leave the allowances of existing tokens uncapped.
+	pub struct MigrateToV3<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v4::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: None,
+				})
+			});
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// `Token` as stored before `transfer_policy` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+	}
+
+	/// Leave the transfers of existing tokens unrestricted.
+	pub struct MigrateToV4<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 4 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v5::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: TransferPolicy::Open,
+				})
+			});
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	/// `Token` as stored before `max_holders` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+	}
+
+	/// Leave the holders of existing tokens uncapped and count their current holders.
+	pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 5 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v6::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: None,
+				})
+			});
+
+			let mut balances: Weight = 0;
+			let mut holders: Weight = 0;
+			for (id, _, balance) in Balances::<T>::iter() {
+				balances += 1;
+				if !balance.is_zero() {
+					holders += 1;
+					HolderCount::<T>::mutate(id, |count| *count = count.saturating_add(1));
+				}
+			}
+
+			StorageVersion::new(5).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(
+				translated.saturating_add(balances).saturating_add(1),
+				translated.saturating_add(holders).saturating_add(1),
+			)
+		}
+	}
+}
+
+pub mod v6 {
+	use super::*;
+
+	/// `Token` as stored before `transfer_cooldown` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+	}
+
+	/// Leave existing tokens without a transfer cooldown.
+	pub struct MigrateToV6<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 6 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v7::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: None,
+				})
+			});
+
+			StorageVersion::new(6).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v7 {
+	use super::*;
+
+	/// `Token` as stored before `require_memo` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+	}
+
+	/// Keep plain transfers of existing tokens allowed.
+	pub struct MigrateToV7<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 7 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v8::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: false,
+				})
+			});
+
+			StorageVersion::new(7).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v8 {
+	use super::*;
+
+	/// `Token` as stored before `max_supply` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+		pub require_memo: bool,
+	}
+
+	/// Leave the supply of existing tokens uncapped.
+	pub struct MigrateToV8<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 8 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v9::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: old.require_memo,
+					max_supply: None,
+				})
+			});
+
+			StorageVersion::new(8).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v9 {
+	use super::*;
+
+	/// `Token` as stored before `whole_units_only` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+		pub require_memo: bool,
+		pub max_supply: Option<Balance>,
+	}
+
+	/// Keep fractional mints of existing tokens allowed.
+	pub struct MigrateToV9<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV9<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 9 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v10::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: old.require_memo,
+					max_supply: old.max_supply,
+					whole_units_only: false,
+				})
+			});
+
+			StorageVersion::new(9).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v10 {
+	use super::*;
+
+	/// `Token` as stored before `emit_transfer_events` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+		pub require_memo: bool,
+		pub max_supply: Option<Balance>,
+		pub whole_units_only: bool,
+	}
+
+	/// Keep depositing transfer events for existing tokens.
+	pub struct MigrateToV10<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV10<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 10 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v11::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: old.require_memo,
+					max_supply: old.max_supply,
+					whole_units_only: old.whole_units_only,
+					emit_transfer_events: true,
+				})
+			});
+
+			StorageVersion::new(10).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v11 {
+	use super::*;
+
+	/// `Token` as stored before `max_tx_amount` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+		pub require_memo: bool,
+		pub max_supply: Option<Balance>,
+		pub whole_units_only: bool,
+		pub emit_transfer_events: bool,
+	}
+
+	/// Leave transfers of existing tokens unlimited in size.
+	pub struct MigrateToV11<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV11<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 11 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(v12::OldToken {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: old.require_memo,
+					max_supply: old.max_supply,
+					whole_units_only: old.whole_units_only,
+					emit_transfer_events: old.emit_transfer_events,
+					max_tx_amount: None,
+				})
+			});
+
+			StorageVersion::new(11).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v12 {
+	use super::*;
+
+	/// `Token` as stored before `max_wallet` was added.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OldToken<AccountId, BoundedString, BlockNumber> {
+		pub owner: AccountId,
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+		pub total_supply: Balance,
+		pub created_at: BlockNumber,
+		pub last_activity: BlockNumber,
+		pub max_allowance: Option<Balance>,
+		pub transfer_policy: TransferPolicy,
+		pub max_holders: Option<u32>,
+		pub transfer_cooldown: Option<BlockNumber>,
+		pub require_memo: bool,
+		pub max_supply: Option<Balance>,
+		pub whole_units_only: bool,
+		pub emit_transfer_events: bool,
+		pub max_tx_amount: Option<Balance>,
+	}
+
+	/// Leave holdings of existing tokens unlimited.
+	pub struct MigrateToV12<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV12<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 12 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated: Weight = 0;
+			Tokens::<T>::translate::<
+				OldToken<T::AccountId, BoundedVec<u8, T::StringLimit>, T::BlockNumber>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(Token {
+					owner: old.owner,
+					name: old.name,
+					symbol: old.symbol,
+					decimals: old.decimals,
+					total_supply: old.total_supply,
+					created_at: old.created_at,
+					last_activity: old.last_activity,
+					max_allowance: old.max_allowance,
+					transfer_policy: old.transfer_policy,
+					max_holders: old.max_holders,
+					transfer_cooldown: old.transfer_cooldown,
+					require_memo: old.require_memo,
+					max_supply: old.max_supply,
+					whole_units_only: old.whole_units_only,
+					emit_transfer_events: old.emit_transfer_events,
+					max_tx_amount: old.max_tx_amount,
+					max_wallet: None,
+				})
+			});
+
+			StorageVersion::new(12).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+		}
+	}
+}
+
+pub mod v13 {
+	use super::*;
+
+	/// Recount the approvals of every owner from their live allowances, so owners from before
+	/// `ApprovalCounts` existed are held to `MaxApprovalsPerOwner` like everyone else.
+	pub struct MigrateToV13<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV13<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 13 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let cleared = ApprovalCounts::<T>::drain().count() as Weight;
+
+			let mut allowances: Weight = 0;
+			let mut approvals: Weight = 0;
+			for (id, (owner, _), allowance) in Allowances::<T>::iter() {
+				allowances += 1;
+				if !allowance.is_zero() {
+					approvals += 1;
+					ApprovalCounts::<T>::mutate(id, owner, |count| {
+						*count = count.saturating_add(1)
+					});
+				}
+			}
+
+			StorageVersion::new(13).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(
+				cleared.saturating_add(allowances).saturating_add(1),
+				cleared.saturating_add(approvals).saturating_add(1),
+			)
+		}
+	}
+}
+
+pub mod v14 {
+	use super::*;
+
+	/// Count the supply of tokens from before `TotalMinted` existed as minted, so
+	/// `TotalMinted - TotalBurned` is their supply too.
+	pub struct MigrateToV14<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV14<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 14 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut tokens: Weight = 0;
+			for (id, token) in Tokens::<T>::iter() {
+				tokens += 1;
+				let burned = TotalBurned::<T>::get(id);
+				TotalMinted::<T>::insert(id, token.total_supply.saturating_add(burned));
+			}
+
+			StorageVersion::new(14).put::<Pallet<T>>();
+
+			T::DbWeight::get()
+				.reads_writes(tokens.saturating_mul(2).saturating_add(1), tokens.saturating_add(1))
+		}
+	}
+}
+
+pub mod v15 {
+	use super::*;
+
+	/// Flag the accounts with a balance entry as having held the token. Emptied balances are
+	/// left as zero entries, so earlier holders that sold out are flagged too.
+	pub struct MigrateToV15<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV15<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 15 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut holders: Weight = 0;
+			for (id, who, _) in Balances::<T>::iter() {
+				holders += 1;
+				EverHeld::<T>::insert(id, who, true);
+			}
+
+			StorageVersion::new(15).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(holders.saturating_add(1), holders.saturating_add(1))
+		}
+	}
+}
+
+pub mod v16 {
+	use super::*;
+
+	/// Count the tokens created before `TokenCount` existed.
+	pub struct MigrateToV16<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV16<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 16 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let tokens = Tokens::<T>::iter().count() as u64;
+			TokenCount::<T>::mutate(|count| *count = (*count).max(tokens));
+
+			StorageVersion::new(16).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(tokens.saturating_add(2), 2)
+		}
+	}
+}
@@ -18,8 +18,8 @@
 
 use crate as pallet_token_fungible;
 use frame_support::{
-	construct_runtime, parameter_types,
-	traits::{ConstU16, ConstU64},
+	assert_ok, construct_runtime, parameter_types,
+	traits::{ConstU16, ConstU64, Randomness},
 	PalletId,
 };
 pub use pallet_balances::Error as BalancesError;
@@ -96,9 +96,45 @@ impl pallet_balances::Config for Test {
 parameter_types! {
 	pub const TokenFungiblePalletId: PalletId = PalletId(*b"w3g/tfpi");
 	pub const StringLimit: u32 = 50;
+	pub static MetadataOverflow: pallet_token_fungible::MetadataOverflowPolicy =
+		pallet_token_fungible::MetadataOverflowPolicy::Reject;
+	pub static NormalizeSymbols: bool = false;
+	pub static EmitBalanceChanged: bool = false;
+	pub static MintFee: Balance = 0;
+	pub static RandomDraws: Vec<u64> = vec![];
+	pub static UnsafeRecipients: Vec<u64> = vec![];
+	pub static MaxApprovalsPerOwner: Option<u32> = None;
+	pub static FeeRounding: pallet_token_fungible::RoundingMode =
+		pallet_token_fungible::RoundingMode::Floor;
+	pub static Reentry: Option<(u64, u64, Balance)> = None;
 	pub const CreateTokenDeposit: Balance = 500 * MILLICENTS;
 }
 
+/// Hands out `RandomDraws` in order, then zeroes.
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		let mut draws = RandomDraws::get();
+		let draw = if draws.is_empty() { 0 } else { draws.remove(0) };
+		RandomDraws::set(&draws);
+		(H256::from_low_u64_be(draw), 0)
+	}
+}
+
+/// Once `Reentry` is armed, the next balance change re-enters the pallet with a transfer of
+/// its amount between its accounts.
+pub struct ReenteringHook;
+
+impl pallet_token_fungible::OnBalanceChanged<u32, u64> for ReenteringHook {
+	fn on_balance_changed(id: u32, _who: &u64, _before: Balance, _after: Balance) {
+		if let Some((from, to, amount)) = Reentry::get() {
+			Reentry::set(&None);
+			assert_ok!(TokenFungible::transfer(Origin::signed(from), id, to, amount));
+		}
+	}
+}
+
 impl pallet_token_fungible::Config for Test {
 	type Event = Event;
 	type PalletId = TokenFungiblePalletId;
@@ -106,6 +142,26 @@ impl pallet_token_fungible::Config for Test {
 	type StringLimit = StringLimit;
 	type CreateTokenDeposit = CreateTokenDeposit;
 	type Currency = Balances;
+	type MaxBatchTransfers = frame_support::traits::ConstU32<10>;
+	type OnMetadataOverflow = MetadataOverflow;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type InactivityThreshold = ConstU64<100>;
+	type NormalizeSymbols = NormalizeSymbols;
+	type DefaultDecimals = frame_support::traits::ConstU8<18>;
+	type EmitBalanceChanged = EmitBalanceChanged;
+	type OnBalanceChanged = ReenteringHook;
+	type MintFee = MintFee;
+	type Randomness = TestRandomness;
+	type MaxLotteryHolders = frame_support::traits::ConstU32<100>;
+	type MaxBatchMints = frame_support::traits::ConstU32<10>;
+	type UnsafeRecipients = frame_support::traits::IsInVec<UnsafeRecipients>;
+	type MinNameLength = frame_support::traits::ConstU32<1>;
+	type MinSymbolLength = frame_support::traits::ConstU32<2>;
+	type MaxApprovalsPerOwner = MaxApprovalsPerOwner;
+	type MaxEmissions = frame_support::traits::ConstU32<10>;
+	type BridgeOrigin = frame_system::EnsureRoot<u64>;
+	type MaxFeeSplits = frame_support::traits::ConstU32<4>;
+	type FeeRounding = FeeRounding;
 	type WeightInfo = ();
 }
 
@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::*;
-use crate::mock::*;
+use crate::mock::{Event, *};
 use frame_support::{assert_noop, assert_ok};
 
 const ALICE: u64 = 1;
@@ -105,6 +105,39 @@ fn mint_should_not_work() {
 	})
 }
 
+#[test]
+fn mint_fee_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		let free = <Test as Config>::Currency::free_balance(&ALICE);
+		let treasury = TokenFungible::treasury_account_id();
+
+		// no fee by default
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(<Test as Config>::Currency::free_balance(&ALICE), free);
+		assert_eq!(<Test as Config>::Currency::free_balance(&treasury), 0);
+
+		MintFee::set(&(10 * CENTS));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 100));
+		assert_ok!(TokenFungible::mint_and_approve(Origin::signed(ALICE), 1, BOB, 100, ALICE, 50));
+		assert_eq!(<Test as Config>::Currency::free_balance(&ALICE), free - 20 * CENTS);
+		assert_eq!(<Test as Config>::Currency::free_balance(&treasury), 20 * CENTS);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 200);
+
+		MintFee::set(&(1_000 * DOLLARS));
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	})
+}
+
 #[test]
 fn burn_should_work() {
 	new_test_ext().execute_with(|| {
@@ -194,7 +227,7 @@ fn approve_should_not_work() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
 			TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 50),
-			Error::<Test>::InsufficientAuthorizedTokens
+			Error::<Test>::InvalidId
 		);
 		assert_ok!(TokenFungible::create_token(
 			Origin::signed(ALICE),
@@ -236,6 +269,12 @@ fn transfer_from_should_work() {
 		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 30);
 		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 20);
 		assert_eq!(TokenFungible::balance_of(1, ALICE), 80);
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, ALICE, BOB, 30,
+		)));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, CHARLIE, 20, 2,
+		)));
 
 		assert_eq!(TokenFungible::total_supply(1), 100);
 	})
@@ -246,11 +285,7 @@ fn transfer_from_should_not_work() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
 			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 20),
-			Error::<Test>::InsufficientAuthorizedTokens
-		);
-		assert_noop!(
-			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, BOB, 20),
-			Error::<Test>::ConfuseBehavior
+			Error::<Test>::InvalidId.with_weight(0)
 		);
 
 		assert_ok!(TokenFungible::create_token(
@@ -268,14 +303,4093 @@ fn transfer_from_should_not_work() {
 
 		assert_noop!(
 			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, BOB, 20),
-			Error::<Test>::ConfuseBehavior
+			Error::<Test>::ConfuseBehavior.with_weight(0)
 		);
 
 		assert_noop!(
 			TokenFungible::transfer_from(Origin::signed(BOB), 1, CHARLIE, ALICE, 20),
-			Error::<Test>::InsufficientAuthorizedTokens
+			Error::<Test>::InsufficientAuthorizedTokens.with_weight(0)
 		);
 
 		assert_eq!(TokenFungible::total_supply(1), 100);
 	})
 }
+
+#[test]
+fn all_tokens_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TokenFungible::all_tokens(None, 10), vec![]);
+
+		for id in 1..=5 {
+			assert_ok!(TokenFungible::create_token(
+				Origin::signed(ALICE),
+				id,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			));
+		}
+
+		let first_page = TokenFungible::all_tokens(None, 2);
+		assert_eq!(first_page.len(), 2);
+		let second_page = TokenFungible::all_tokens(Some(first_page[1].0), 2);
+		assert_eq!(second_page.len(), 2);
+		let last_page = TokenFungible::all_tokens(Some(second_page[1].0), 2);
+		assert_eq!(last_page.len(), 1);
+
+		// beyond the end
+		assert_eq!(TokenFungible::all_tokens(Some(last_page[0].0), 2), vec![]);
+
+		let mut ids = first_page
+			.iter()
+			.chain(second_page.iter())
+			.chain(last_page.iter())
+			.map(|(id, _)| *id)
+			.collect::<Vec<_>>();
+		ids.sort();
+		assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+
+		let (_, info) = &first_page[0];
+		assert_eq!(info.owner, ALICE);
+		assert_eq!(info.name, b"W3G".to_vec());
+		assert_eq!(info.symbol, b"W3G".to_vec());
+		assert_eq!(info.decimals, 18);
+		assert_eq!(info.total_supply, 0);
+
+		assert_eq!(TokenFungible::token_info(first_page[0].0).as_ref(), Some(info));
+		assert_eq!(TokenFungible::token_info(9), None);
+	})
+}
+
+#[test]
+fn transfer_to_full_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, Balance::MAX));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10),
+			Error::<Test>::NumOverflow
+		);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 100);
+		assert_eq!(TokenFungible::balance_of(1, BOB), Balance::MAX);
+	})
+}
+
+#[test]
+fn fungibles_metadata_inspect_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"Web3Games".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"Tether".to_vec(),
+			b"USDT".to_vec(),
+			6
+		));
+
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::name(&1), b"Web3Games");
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::symbol(&1), b"W3G");
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::decimals(&1), 18);
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::name(&2), b"Tether");
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::symbol(&2), b"USDT");
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::decimals(&2), 6);
+
+		// matches the stored metadata
+		assert_eq!(
+			<TokenFungible as fungibles::metadata::Inspect<u64>>::name(&1),
+			TokenFungible::token_name(1)
+		);
+		assert_eq!(
+			<TokenFungible as fungibles::metadata::Inspect<u64>>::decimals(&2),
+			TokenFungible::token_decimals(2)
+		);
+
+		// unknown token
+		assert_eq!(
+			<TokenFungible as fungibles::metadata::Inspect<u64>>::name(&3),
+			Vec::<u8>::new()
+		);
+		assert_eq!(<TokenFungible as fungibles::metadata::Inspect<u64>>::decimals(&3), 0);
+	})
+}
+
+#[test]
+fn fungibles_inspect_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_eq!(<TokenFungible as fungibles::Inspect<u64>>::total_issuance(1), 100);
+		assert_eq!(<TokenFungible as fungibles::Inspect<u64>>::balance(1, &ALICE), 100);
+		assert_eq!(
+			<TokenFungible as fungibles::Inspect<u64>>::can_withdraw(1, &ALICE, 101),
+			WithdrawConsequence::Underflow
+		);
+		assert_eq!(
+			<TokenFungible as fungibles::Inspect<u64>>::can_withdraw(1, &BOB, 10),
+			WithdrawConsequence::NoFunds
+		);
+		assert_eq!(
+			<TokenFungible as fungibles::Inspect<u64>>::can_deposit(2, &ALICE, 10, true),
+			DepositConsequence::UnknownAsset
+		);
+		assert_eq!(
+			<TokenFungible as fungibles::Inspect<u64>>::can_deposit(1, &BOB, 10, true),
+			DepositConsequence::Success
+		);
+	})
+}
+
+fn transfer_events() -> usize {
+	System::events()
+		.iter()
+		.filter(|record| {
+			matches!(record.event, Event::TokenFungible(TokenFungibleEvent::Transfer(..)))
+		})
+		.count()
+}
+
+#[test]
+fn transfer_batch_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		System::reset_events();
+		assert_ok!(TokenFungible::transfer_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 10), (CHARLIE, 20)],
+			true
+		));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 70);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 10);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 20);
+		assert_eq!(transfer_events(), 0);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::BatchTransfer(
+			1, ALICE, 2, 30, 2,
+		)));
+
+		System::reset_events();
+		assert_ok!(TokenFungible::transfer_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 10), (CHARLIE, 20)],
+			false
+		));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 40);
+		assert_eq!(transfer_events(), 2);
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, BOB, 10, 3,
+		)));
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, CHARLIE, 20, 4,
+		)));
+	})
+}
+
+#[test]
+fn transfer_batch_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::transfer_batch(Origin::signed(ALICE), 1, vec![(BOB, 1); 11], true),
+			Error::<Test>::TooManyTransfers.with_weight(0)
+		);
+		assert_noop!(
+			TokenFungible::transfer_batch(
+				Origin::signed(ALICE),
+				1,
+				vec![(BOB, 60), (CHARLIE, 60)],
+				true
+			),
+			Error::<Test>::InsufficientTokens
+		);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+	})
+}
+
+#[test]
+fn spent_allowance_should_be_removed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 50));
+		assert!(Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 20));
+		assert!(Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 30));
+
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 50);
+
+		// approving nothing doesn't create an entry either
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 0));
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+	})
+}
+
+#[test]
+fn validate_transfer_from_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 50));
+
+		assert_ok!(TokenFungible::validate_transfer_from(1, &BOB, &ALICE, &CHARLIE, 50));
+		assert_eq!(
+			TokenFungible::validate_transfer_from(1, &BOB, &ALICE, &BOB, 50),
+			Err(Error::<Test>::ConfuseBehavior.into())
+		);
+		assert_eq!(
+			TokenFungible::validate_transfer_from(1, &BOB, &ALICE, &CHARLIE, 51),
+			Err(Error::<Test>::InsufficientAuthorizedTokens.into())
+		);
+
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 100));
+		assert_eq!(
+			TokenFungible::validate_transfer_from(1, &BOB, &ALICE, &CHARLIE, 150),
+			Err(Error::<Test>::InsufficientTokens.into())
+		);
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, Balance::MAX));
+		assert_eq!(
+			TokenFungible::validate_transfer_from(1, &BOB, &ALICE, &CHARLIE, 10),
+			Err(Error::<Test>::NumOverflow.into())
+		);
+
+		// validation never touches storage
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 150);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 100);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), Balance::MAX);
+	})
+}
+
+#[test]
+fn create_token_named_should_work() {
+	new_test_ext().execute_with(|| {
+		// an explicitly created token occupies the first free id
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			0,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"USDT".to_vec(),
+			b"Tether".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(BOB),
+			b"DOT".to_vec(),
+			b"Polkadot".to_vec(),
+			10
+		));
+
+		assert_eq!(TokenFungible::token_id_by_symbol(b"USDT"), Some(1));
+		assert_eq!(TokenFungible::token_id_by_symbol(b"DOT"), Some(2));
+		assert_eq!(TokenFungible::token_id_by_symbol(b"W3G"), None);
+		assert_eq!(TokenFungible::token_symbol(1), b"USDT".to_vec());
+		assert_eq!(TokenFungible::token_decimals(2), 10);
+	})
+}
+
+#[test]
+fn create_token_named_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"USDT".to_vec(),
+			b"Tether".to_vec(),
+			6
+		));
+
+		assert_noop!(
+			TokenFungible::create_token_named(
+				Origin::signed(BOB),
+				b"USDT".to_vec(),
+				b"Fake Tether".to_vec(),
+				6
+			),
+			Error::<Test>::DuplicateSymbol
+		);
+		assert_eq!(TokenFungible::token_id_by_symbol(b"USDT"), Some(0));
+	})
+}
+
+#[test]
+fn transfer_fee_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1000));
+
+		assert_noop!(
+			TokenFungible::set_transfer_fee(Origin::signed(BOB), 1, 100),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, MAX_BPS + 1),
+			Error::<Test>::InvalidFee
+		);
+		// 1%
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 100));
+
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 500));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 500);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 495);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 5);
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, ALICE, 5, 2,
+		)));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, CHARLIE, 495, 3,
+		)));
+
+		// the owner doesn't pay its own fee
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 5));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 500);
+	})
+}
+
+#[test]
+fn transfer_checked_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1000));
+
+		// without a fee the full amount always arrives
+		assert_ok!(TokenFungible::transfer_checked(Origin::signed(BOB), 1, CHARLIE, 100, 100));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 100);
+
+		// 2%
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 200));
+
+		assert_noop!(
+			TokenFungible::transfer_checked(Origin::signed(BOB), 1, CHARLIE, 100, 99),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_ok!(TokenFungible::transfer_checked(Origin::signed(BOB), 1, CHARLIE, 100, 98));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 198);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 2);
+
+		// 10% reflected from the 98 left after the transfer fee
+		assert_ok!(TokenFungible::set_reflection_fee(Origin::signed(ALICE), 1, 1_000));
+		assert_eq!(TokenFungible::reflection_fee(1, 98), 9);
+		assert_noop!(
+			TokenFungible::transfer_checked(Origin::signed(BOB), 1, 4, 100, 90),
+			Error::<Test>::SlippageExceeded
+		);
+		assert_ok!(TokenFungible::transfer_checked(Origin::signed(BOB), 1, 4, 100, 89));
+		assert_eq!(TokenFungible::balance_of(1, 4), 89);
+
+		// aggregated batches pay the same fees
+		assert_ok!(TokenFungible::transfer_batch(Origin::signed(BOB), 1, vec![(5, 100)], true));
+		assert_eq!(TokenFungible::balance_of(1, 5), 89);
+	})
+}
+
+#[test]
+fn format_balance_should_work() {
+	assert_eq!(TokenFungible::format_balance(1_500_000, 6), b"1.5".to_vec());
+	assert_eq!(TokenFungible::format_balance(1_000_000, 6), b"1".to_vec());
+	assert_eq!(TokenFungible::format_balance(1, 6), b"0.000001".to_vec());
+	assert_eq!(TokenFungible::format_balance(0, 6), b"0".to_vec());
+	assert_eq!(TokenFungible::format_balance(0, 0), b"0".to_vec());
+	assert_eq!(TokenFungible::format_balance(1234, 0), b"1234".to_vec());
+	assert_eq!(
+		TokenFungible::format_balance(Balance::MAX, 18),
+		b"340282366920938463463.374607431768211455".to_vec()
+	);
+	assert_eq!(
+		TokenFungible::format_balance(Balance::MAX, 40),
+		b"0.0340282366920938463463374607431768211455".to_vec()
+	);
+}
+
+#[test]
+fn formatted_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"USDT".to_vec(),
+			b"USDT".to_vec(),
+			6
+		));
+		assert_eq!(TokenFungible::formatted_balance(1, &ALICE), b"0".to_vec());
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1_500_000));
+		assert_eq!(TokenFungible::formatted_balance(1, &ALICE), b"1.5".to_vec());
+	})
+}
+
+#[test]
+fn scale_amount_should_work() {
+	assert_eq!(TokenFungible::scale_amount(0, 18), Some(0));
+	assert_eq!(TokenFungible::scale_amount(15, 0), Some(15));
+	assert_eq!(TokenFungible::scale_amount(15, 6), Some(15_000_000));
+	assert_eq!(TokenFungible::scale_amount(1, 18), Some(1_000_000_000_000_000_000));
+	assert_eq!(TokenFungible::scale_amount(1, 38), Some(10u128.pow(38)));
+
+	// overflow
+	assert_eq!(TokenFungible::scale_amount(4, 38), None);
+	assert_eq!(TokenFungible::scale_amount(0, 39), None);
+	assert_eq!(TokenFungible::scale_amount(u128::MAX, 1), None);
+}
+
+#[test]
+fn unknown_token_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 0),
+			Error::<Test>::InvalidId
+		);
+		assert_noop!(
+			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 0),
+			Error::<Test>::InvalidId.with_weight(0)
+		);
+		assert_noop!(
+			TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 0),
+			Error::<Test>::InvalidId
+		);
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+	})
+}
+
+#[test]
+fn permit_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TokenFungible::do_permit(1, &ALICE, &BOB, 50), Error::<Test>::InvalidId);
+
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(TokenFungible::permit_nonce(1, ALICE), 0);
+
+		assert_ok!(TokenFungible::do_permit(1, &ALICE, &BOB, 50));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 50);
+		assert_eq!(TokenFungible::permit_nonce(1, ALICE), 1);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, ALICE, BOB, 50,
+		)));
+
+		// a permit replaces the allowance rather than adding to it, whatever the balance
+		assert_ok!(TokenFungible::do_permit(1, &ALICE, &BOB, 30));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 30);
+		assert_ok!(TokenFungible::do_permit(1, &ALICE, &BOB, 500));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 500);
+		assert_ok!(TokenFungible::do_permit(1, &ALICE, &BOB, 0));
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 0);
+		assert_eq!(TokenFungible::permit_nonce(1, ALICE), 4);
+
+		// a failed approval doesn't burn the nonce
+		assert_noop!(
+			TokenFungible::do_permit(1, &ALICE, &ALICE, 50),
+			Error::<Test>::ApproveToCurrentOwner
+		);
+		MaxApprovalsPerOwner::set(&Some(1));
+		assert_ok!(TokenFungible::do_permit(1, &ALICE, &BOB, 50));
+		assert_noop!(
+			TokenFungible::do_permit(1, &ALICE, &CHARLIE, 50),
+			Error::<Test>::TooManyApprovals
+		);
+		MaxApprovalsPerOwner::set(&None);
+		assert_eq!(TokenFungible::permit_nonce(1, ALICE), 5);
+	})
+}
+
+#[test]
+fn set_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_noop!(
+			TokenFungible::set_metadata(Origin::signed(BOB), 0, b"New".to_vec(), b"NEW".to_vec()),
+			Error::<Test>::NoPermission
+		);
+
+		assert_ok!(TokenFungible::set_metadata(
+			Origin::signed(ALICE),
+			0,
+			b"Web3Games".to_vec(),
+			b"WGT".to_vec()
+		));
+		assert_eq!(TokenFungible::token_name(0), b"Web3Games".to_vec());
+		assert_eq!(TokenFungible::token_symbol(0), b"WGT".to_vec());
+		// the registry follows the symbol
+		assert_eq!(TokenFungible::token_id_by_symbol(b"WGT"), Some(0));
+		assert_eq!(TokenFungible::token_id_by_symbol(b"W3G"), None);
+	})
+}
+
+#[test]
+fn metadata_overflow_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		let long_name = vec![b'a'; 51];
+
+		assert_noop!(
+			TokenFungible::create_token(
+				Origin::signed(ALICE),
+				1,
+				long_name.clone(),
+				b"W3G".to_vec(),
+				18
+			),
+			Error::<Test>::BadMetadata
+		);
+
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::set_metadata(Origin::signed(ALICE), 1, long_name, b"W3G".to_vec()),
+			Error::<Test>::BadMetadata
+		);
+	})
+}
+
+#[test]
+fn metadata_overflow_should_be_truncated() {
+	new_test_ext().execute_with(|| {
+		MetadataOverflow::set(&MetadataOverflowPolicy::Truncate);
+		let long_name = vec![b'a'; 51];
+
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			long_name.clone(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_eq!(TokenFungible::token_name(1), vec![b'a'; 50]);
+
+		assert_ok!(TokenFungible::set_metadata(
+			Origin::signed(ALICE),
+			1,
+			vec![b'b'; 60],
+			b"W3G".to_vec()
+		));
+		assert_eq!(TokenFungible::token_name(1), vec![b'b'; 50]);
+
+		MetadataOverflow::set(&MetadataOverflowPolicy::Reject);
+	})
+}
+
+#[test]
+fn created_at_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TokenFungible::created_at(1), None);
+
+		System::set_block_number(3);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_eq!(TokenFungible::created_at(1), Some(3));
+	})
+}
+
+#[test]
+fn migrate_to_v1_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TokenFungible>();
+
+		let old = migrations::v1::OldToken {
+			owner: ALICE,
+			name: BoundedVec::<u8, StringLimit>::try_from(b"W3G".to_vec()).unwrap(),
+			symbol: BoundedVec::<u8, StringLimit>::try_from(b"W3G".to_vec()).unwrap(),
+			decimals: 18,
+			total_supply: 100,
+		};
+		frame_support::storage::unhashed::put(&Tokens::<Test>::hashed_key_for(1), &old);
+
+		System::set_block_number(5);
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(1));
+
+		// running it again is a no-op
+		System::set_block_number(6);
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+		migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+		migrations::v4::MigrateToV4::<Test>::on_runtime_upgrade();
+		migrations::v5::MigrateToV5::<Test>::on_runtime_upgrade();
+		migrations::v6::MigrateToV6::<Test>::on_runtime_upgrade();
+		migrations::v7::MigrateToV7::<Test>::on_runtime_upgrade();
+		migrations::v8::MigrateToV8::<Test>::on_runtime_upgrade();
+		migrations::v9::MigrateToV9::<Test>::on_runtime_upgrade();
+		migrations::v10::MigrateToV10::<Test>::on_runtime_upgrade();
+		migrations::v11::MigrateToV11::<Test>::on_runtime_upgrade();
+		migrations::v12::MigrateToV12::<Test>::on_runtime_upgrade();
+		migrations::v13::MigrateToV13::<Test>::on_runtime_upgrade();
+		migrations::v14::MigrateToV14::<Test>::on_runtime_upgrade();
+		migrations::v15::MigrateToV15::<Test>::on_runtime_upgrade();
+		migrations::v16::MigrateToV16::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TokenFungible::created_at(1), Some(5));
+		assert_eq!(TokenFungible::token_name(1), b"W3G".to_vec());
+		assert_eq!(TokenFungible::total_supply(1), 100);
+	})
+}
+
+#[test]
+fn freeze_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::set_metadata(
+			Origin::signed(ALICE),
+			1,
+			b"Web3Games".to_vec(),
+			b"WGT".to_vec()
+		));
+
+		assert_noop!(
+			TokenFungible::freeze_metadata(Origin::signed(BOB), 1),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::freeze_metadata(Origin::signed(ALICE), 1));
+		assert!(TokenFungible::metadata_frozen(1));
+
+		assert_noop!(
+			TokenFungible::set_metadata(Origin::signed(ALICE), 1, b"W3G".to_vec(), b"W3G".to_vec()),
+			Error::<Test>::MetadataFrozen
+		);
+
+		// freezing again changes nothing, there's no way back
+		assert_ok!(TokenFungible::freeze_metadata(Origin::signed(ALICE), 1));
+		assert!(TokenFungible::metadata_frozen(1));
+		assert_eq!(TokenFungible::token_name(1), b"Web3Games".to_vec());
+	})
+}
+
+#[test]
+fn mint_and_approve_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_noop!(
+			TokenFungible::mint_and_approve(Origin::signed(BOB), 1, BOB, 100, CHARLIE, 50),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::mint_and_approve(Origin::signed(ALICE), 1, BOB, 100, BOB, 50),
+			Error::<Test>::ApproveToCurrentOwner
+		);
+
+		assert_ok!(TokenFungible::mint_and_approve(
+			Origin::signed(ALICE),
+			1,
+			BOB,
+			100,
+			CHARLIE,
+			50
+		));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+		assert_eq!(TokenFungible::allowances(1, (BOB, CHARLIE)), 50);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, BOB, CHARLIE, 50,
+		)));
+
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, BOB, ALICE, 50));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 50);
+	})
+}
+
+#[test]
+fn event_seq_should_increase() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_eq!(TokenFungible::event_seq(), 0);
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Mint(
+			1, 0, ALICE, 100, 1,
+		)));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, BOB, 10, 2,
+		)));
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, ALICE, 10));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, ALICE, 10, 3,
+		)));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 10));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, 0, 10, 4,
+		)));
+		assert_eq!(TokenFungible::event_seq(), 4);
+
+		// a failed operation doesn't consume a sequence number
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, ALICE, 10),
+			Error::<Test>::InsufficientTokens
+		);
+		assert_eq!(TokenFungible::event_seq(), 4);
+	})
+}
+
+#[test]
+fn failed_transfer_from_should_refund_weight() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 50));
+
+		let full_weight = <() as WeightInfo>::transfer_from();
+
+		let err =
+			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 60).unwrap_err();
+		assert_eq!(err.error, Error::<Test>::InsufficientAuthorizedTokens.into());
+		assert!(err.post_info.actual_weight.unwrap() < full_weight);
+
+		// a successful transfer is charged the full weight
+		let post_info =
+			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 50).unwrap();
+		assert_eq!(post_info.actual_weight, None);
+	})
+}
+
+#[test]
+fn approve_should_deposit_approval_event() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		System::reset_events();
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 50));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 30));
+
+		// a plain extrinsic has no EVM frame, so only the pallet events are deposited
+		assert_eq!(
+			System::events().into_iter().map(|r| r.event).collect::<Vec<_>>(),
+			vec![
+				Event::TokenFungible(TokenFungibleEvent::Approval(1, ALICE, BOB, 50)),
+				Event::TokenFungible(TokenFungibleEvent::Approval(1, ALICE, BOB, 80)),
+			]
+		);
+	})
+}
+
+#[test]
+fn aggregate_counters_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TokenFungible::token_count(), 0);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"USDT".to_vec(),
+			b"USDT".to_vec(),
+			6
+		));
+		assert_eq!(TokenFungible::token_count(), 2);
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 2, BOB, 100));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 40));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 5));
+		assert_eq!(TokenFungible::mint_count(), 3);
+		assert_eq!(TokenFungible::burn_count(), 1);
+
+		// failed operations are not counted
+		assert_noop!(
+			TokenFungible::burn(Origin::signed(ALICE), 1, 1000),
+			Error::<Test>::NumOverflow
+		);
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(BOB), 1, BOB, 1),
+			Error::<Test>::NoPermission
+		);
+		assert_eq!(TokenFungible::mint_count(), 3);
+		assert_eq!(TokenFungible::burn_count(), 1);
+	})
+}
+
+#[test]
+fn reap_inactive_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&ALICE), CreateTokenDeposit::get());
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::DepositReserved(
+			1,
+			ALICE,
+			CreateTokenDeposit::get(),
+		)));
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		System::set_block_number(50);
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 100));
+
+		// the threshold counts from the last burn, not from creation
+		System::set_block_number(150);
+		assert_noop!(
+			TokenFungible::reap_inactive(Origin::root(), 1),
+			Error::<Test>::TokenStillActive
+		);
+
+		System::set_block_number(151);
+		assert_noop!(
+			TokenFungible::reap_inactive(Origin::signed(ALICE), 1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(TokenFungible::reap_inactive(Origin::root(), 1));
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::DepositReturned(
+			1,
+			ALICE,
+			CreateTokenDeposit::get(),
+		)));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::TokenReaped(1)));
+		assert!(!TokenFungible::exists(1));
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&ALICE), 0);
+		assert!(!TokenFungible::is_reaping(1));
+		// a token re-created under the id starts without holders
+		assert!(!TokenFungible::has_ever_held(1, ALICE));
+	})
+}
+
+#[test]
+fn reap_inactive_should_clear_in_rounds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		// emptied balances are left behind as zero entries
+		for account in 100..(100 + u64::from(MAX_REAP_REMOVALS)) {
+			Balances::<Test>::insert(1, account, 0);
+		}
+		Allowances::<Test>::insert(1, (ALICE, BOB), 10);
+		EverHeld::<Test>::insert(1, BOB, true);
+		// a provider the pallet still holds for an account
+		System::inc_providers(&4);
+		ProvidedAccounts::<Test>::insert(1, 4, true);
+
+		System::set_block_number(200);
+		assert_ok!(TokenFungible::reap_inactive(Origin::root(), 1));
+		assert!(!TokenFungible::exists(1));
+		assert!(TokenFungible::is_reaping(1));
+		assert_eq!(Balances::<Test>::iter_prefix(1).count(), 0);
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 10);
+
+		// the id stays taken until the rest is cleared
+		assert_noop!(
+			TokenFungible::create_token(
+				Origin::signed(BOB),
+				1,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			),
+			Error::<Test>::InvalidId
+		);
+
+		assert_ok!(TokenFungible::reap_inactive(Origin::root(), 1));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::TokenReaped(1)));
+		assert!(!TokenFungible::is_reaping(1));
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+		assert!(!EverHeld::<Test>::contains_key(1, BOB));
+		assert!(!ProvidedAccounts::<Test>::contains_key(1, 4));
+		assert!(!System::account_exists(&4));
+		assert_noop!(TokenFungible::reap_inactive(Origin::root(), 1), Error::<Test>::InvalidId);
+
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+	})
+}
+
+#[test]
+fn reap_inactive_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TokenFungible::reap_inactive(Origin::root(), 1), Error::<Test>::InvalidId);
+
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		// a transfer keeps the token alive
+		System::set_block_number(100);
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 100));
+		System::set_block_number(200);
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, ALICE, 100));
+		assert_eq!(Tokens::<Test>::get(1).unwrap().last_activity, 200);
+
+		// tokens with supply left are never reaped
+		System::set_block_number(1000);
+		assert_noop!(TokenFungible::reap_inactive(Origin::root(), 1), Error::<Test>::TokenNotEmpty);
+	})
+}
+
+#[test]
+fn migrate_to_v2_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<TokenFungible>();
+
+		let old = migrations::v2::OldToken {
+			owner: ALICE,
+			name: BoundedVec::<u8, StringLimit>::try_from(b"W3G".to_vec()).unwrap(),
+			symbol: BoundedVec::<u8, StringLimit>::try_from(b"W3G".to_vec()).unwrap(),
+			decimals: 18,
+			total_supply: 100,
+			created_at: 2u64,
+		};
+		frame_support::storage::unhashed::put(&Tokens::<Test>::hashed_key_for(1), &old);
+		crate::Balances::<Test>::insert(1, ALICE, 60);
+		crate::Balances::<Test>::insert(1, BOB, 40);
+		crate::Balances::<Test>::insert(1, CHARLIE, 0);
+
+		System::set_block_number(5);
+		migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(2));
+		migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+		migrations::v4::MigrateToV4::<Test>::on_runtime_upgrade();
+		migrations::v5::MigrateToV5::<Test>::on_runtime_upgrade();
+		migrations::v6::MigrateToV6::<Test>::on_runtime_upgrade();
+		migrations::v7::MigrateToV7::<Test>::on_runtime_upgrade();
+		migrations::v8::MigrateToV8::<Test>::on_runtime_upgrade();
+		migrations::v9::MigrateToV9::<Test>::on_runtime_upgrade();
+		migrations::v10::MigrateToV10::<Test>::on_runtime_upgrade();
+		migrations::v11::MigrateToV11::<Test>::on_runtime_upgrade();
+		migrations::v12::MigrateToV12::<Test>::on_runtime_upgrade();
+		migrations::v13::MigrateToV13::<Test>::on_runtime_upgrade();
+		migrations::v14::MigrateToV14::<Test>::on_runtime_upgrade();
+		migrations::v15::MigrateToV15::<Test>::on_runtime_upgrade();
+		migrations::v16::MigrateToV16::<Test>::on_runtime_upgrade();
+
+		let token = Tokens::<Test>::get(1).unwrap();
+		assert_eq!(token.created_at, 2);
+		assert_eq!(token.last_activity, 5);
+		assert_eq!(token.max_allowance, None);
+		assert_eq!(token.transfer_policy, TransferPolicy::Open);
+		assert_eq!(token.max_holders, None);
+		assert_eq!(token.transfer_cooldown, None);
+		assert!(!token.require_memo);
+		assert_eq!(token.max_supply, None);
+		assert!(!token.whole_units_only);
+		assert!(token.emit_transfer_events);
+		assert_eq!(token.max_tx_amount, None);
+		assert_eq!(token.max_wallet, None);
+		assert_eq!(TokenFungible::holder_count(1), 2);
+		assert!(TokenFungible::has_ever_held(1, BOB));
+		assert_eq!(TokenFungible::token_count(), 1);
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(16));
+	})
+}
+
+#[test]
+fn migrate_to_v13_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(12).put::<TokenFungible>();
+
+		// allowances granted before approvals were counted, plus a stale count
+		Allowances::<Test>::insert(1, (ALICE, BOB), 10);
+		Allowances::<Test>::insert(1, (ALICE, CHARLIE), 20);
+		Allowances::<Test>::insert(1, (ALICE, 4), 0);
+		Allowances::<Test>::insert(2, (ALICE, BOB), 5);
+		ApprovalCounts::<Test>::insert(1, BOB, 3);
+
+		migrations::v13::MigrateToV13::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 2);
+		assert_eq!(TokenFungible::approvals_count(2, ALICE), 1);
+		assert_eq!(TokenFungible::approvals_count(1, BOB), 0);
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(13));
+
+		// running it again is a no-op
+		Allowances::<Test>::insert(1, (ALICE, 5), 10);
+		migrations::v13::MigrateToV13::<Test>::on_runtime_upgrade();
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 2);
+	})
+}
+
+#[test]
+fn migrate_to_v14_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		// a token whose mints weren't counted yet
+		StorageVersion::new(13).put::<TokenFungible>();
+		TotalMinted::<Test>::remove(1);
+
+		migrations::v14::MigrateToV14::<Test>::on_runtime_upgrade();
+		assert_eq!(TokenFungible::total_minted(1), 100);
+		assert_eq!(TokenFungible::total_burned(1), 0);
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(14));
+
+		// burns after the upgrade keep the counters in step with the supply
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 30));
+		assert_eq!(
+			TokenFungible::total_minted(1) - TokenFungible::total_burned(1),
+			TokenFungible::total_supply(1)
+		);
+
+		// running it again is a no-op
+		migrations::v14::MigrateToV14::<Test>::on_runtime_upgrade();
+		assert_eq!(TokenFungible::total_minted(1), 100);
+	})
+}
+
+#[test]
+fn migrate_to_v15_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(14).put::<TokenFungible>();
+
+		// a holder and one that sold out before holders were flagged
+		crate::Balances::<Test>::insert(1, ALICE, 60);
+		crate::Balances::<Test>::insert(1, BOB, 0);
+		crate::Balances::<Test>::insert(2, CHARLIE, 5);
+
+		migrations::v15::MigrateToV15::<Test>::on_runtime_upgrade();
+		assert!(TokenFungible::has_ever_held(1, ALICE));
+		assert!(TokenFungible::has_ever_held(1, BOB));
+		assert!(!TokenFungible::has_ever_held(1, CHARLIE));
+		assert!(TokenFungible::has_ever_held(2, CHARLIE));
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(15));
+
+		// running it again is a no-op
+		crate::Balances::<Test>::insert(1, CHARLIE, 5);
+		migrations::v15::MigrateToV15::<Test>::on_runtime_upgrade();
+		assert!(!TokenFungible::has_ever_held(1, CHARLIE));
+	})
+}
+
+#[test]
+fn migrate_to_v16_should_work() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		for id in 1..=3 {
+			assert_ok!(TokenFungible::create_token(
+				Origin::signed(ALICE),
+				id,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			));
+		}
+		// tokens created before they were counted
+		StorageVersion::new(15).put::<TokenFungible>();
+		TokenCount::<Test>::kill();
+
+		migrations::v16::MigrateToV16::<Test>::on_runtime_upgrade();
+		assert_eq!(TokenFungible::token_count(), 3);
+		assert_eq!(StorageVersion::get::<TokenFungible>(), StorageVersion::new(16));
+
+		// running it again is a no-op
+		TokenCount::<Test>::put(5);
+		migrations::v16::MigrateToV16::<Test>::on_runtime_upgrade();
+		assert_eq!(TokenFungible::token_count(), 5);
+	})
+}
+
+#[test]
+fn normalized_symbols_should_collide() {
+	new_test_ext().execute_with(|| {
+		NormalizeSymbols::set(&true);
+
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"USD".to_vec(),
+			b"Dollar".to_vec(),
+			6
+		));
+		assert_noop!(
+			TokenFungible::create_token_named(
+				Origin::signed(BOB),
+				b" usd ".to_vec(),
+				b"Dollar".to_vec(),
+				6
+			),
+			Error::<Test>::DuplicateSymbol
+		);
+		assert_eq!(TokenFungible::token_id_by_symbol(b"uSd"), Some(0));
+
+		// the token keeps the symbol as given
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(BOB),
+			b"eur".to_vec(),
+			b"Euro".to_vec(),
+			6
+		));
+		assert_eq!(TokenFungible::token_symbol(1), b"eur".to_vec());
+		assert_eq!(TokenFungible::token_id_by_symbol(b"EUR"), Some(1));
+
+		NormalizeSymbols::set(&false);
+	})
+}
+
+#[test]
+fn unnormalized_symbols_should_not_collide() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"USD".to_vec(),
+			b"Dollar".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(BOB),
+			b"usd".to_vec(),
+			b"Dollar".to_vec(),
+			6
+		));
+		assert_eq!(TokenFungible::token_id_by_symbol(b"USD"), Some(0));
+		assert_eq!(TokenFungible::token_id_by_symbol(b"usd"), Some(1));
+		assert_eq!(TokenFungible::token_symbol(1), b"usd".to_vec());
+	})
+}
+
+#[test]
+fn has_ever_held_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert!(!TokenFungible::has_ever_held(1, ALICE));
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert!(TokenFungible::has_ever_held(1, ALICE));
+
+		// a zero transfer doesn't count as holding
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 0));
+		assert!(!TokenFungible::has_ever_held(1, CHARLIE));
+
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 40));
+		assert!(TokenFungible::has_ever_held(1, BOB));
+
+		// the flag outlives the balance
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, ALICE, 40));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 100));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert!(TokenFungible::has_ever_held(1, ALICE));
+		assert!(TokenFungible::has_ever_held(1, BOB));
+	})
+}
+
+#[test]
+fn change_allowance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		// no balance is needed to raise an allowance
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 50));
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 20));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 70);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, ALICE, BOB, 70,
+		)));
+
+		assert_ok!(TokenFungible::decrease_allowance(Origin::signed(ALICE), 1, BOB, 30));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 40);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, ALICE, BOB, 40,
+		)));
+
+		assert_ok!(TokenFungible::decrease_allowance(Origin::signed(ALICE), 1, BOB, 40));
+		assert!(!Allowances::<Test>::contains_key(1, (ALICE, BOB)));
+	})
+}
+
+#[test]
+fn change_allowance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 50),
+			Error::<Test>::InvalidId
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_noop!(
+			TokenFungible::increase_allowance(Origin::signed(ALICE), 1, ALICE, 50),
+			Error::<Test>::ApproveToCurrentOwner
+		);
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 50));
+		assert_noop!(
+			TokenFungible::decrease_allowance(Origin::signed(ALICE), 1, BOB, 51),
+			Error::<Test>::AllowanceBelowZero
+		);
+		assert_noop!(
+			TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, Balance::MAX),
+			Error::<Test>::NumOverflow
+		);
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 50);
+	})
+}
+
+#[test]
+fn create_token_default_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			0,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::create_token_default(
+			Origin::signed(BOB),
+			b"Gold".to_vec(),
+			b"GLD".to_vec()
+		));
+
+		assert!(TokenFungible::exists(1));
+		assert_eq!(TokenFungible::token_decimals(1), 18);
+		assert_eq!(TokenFungible::token_symbol(1), b"GLD".to_vec());
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::TokenCreated(
+			1,
+			BOB,
+			b"Gold".to_vec(),
+			b"GLD".to_vec(),
+			18,
+		)));
+	})
+}
+
+#[test]
+fn operator_transfer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_ok!(TokenFungible::set_operator(Origin::signed(ALICE), 1, BOB, 50));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::OperatorSet(
+			1, ALICE, BOB, 50,
+		)));
+
+		assert_ok!(TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 30));
+		assert_ok!(TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, BOB, 20));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 50);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 30);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 20);
+		assert_eq!(TokenFungible::operator_spent(1, (ALICE, BOB)), 50);
+
+		// operators don't touch allowances
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 0);
+
+		assert_noop!(
+			TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 1),
+			Error::<Test>::OperatorCapExceeded
+		);
+
+		// a new cap starts from scratch
+		assert_ok!(TokenFungible::set_operator(Origin::signed(ALICE), 1, BOB, 10));
+		assert_eq!(TokenFungible::operator_spent(1, (ALICE, BOB)), 0);
+		assert_ok!(TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 10));
+	})
+}
+
+#[test]
+fn operator_transfer_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 1),
+			Error::<Test>::NotOperator
+		);
+		assert_noop!(
+			TokenFungible::set_operator(Origin::signed(ALICE), 1, ALICE, 50),
+			Error::<Test>::ApproveToCurrentOwner
+		);
+
+		assert_ok!(TokenFungible::set_operator(Origin::signed(ALICE), 1, BOB, 500));
+		assert_noop!(
+			TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 200),
+			Error::<Test>::InsufficientTokens
+		);
+
+		// revocation
+		assert_ok!(TokenFungible::set_operator(Origin::signed(ALICE), 1, BOB, 0));
+		assert!(!OperatorCaps::<Test>::contains_key(1, (ALICE, BOB)));
+		assert_noop!(
+			TokenFungible::operator_transfer(Origin::signed(BOB), 1, ALICE, CHARLIE, 1),
+			Error::<Test>::NotOperator
+		);
+	})
+}
+
+#[test]
+fn zero_account_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, 0, 100),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, 0, 10),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(0), 1, ALICE, 0),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(TokenFungible::burn(Origin::signed(0), 1, 0), Error::<Test>::InvalidAccount);
+
+		assert_eq!(TokenFungible::balance_of(1, 0), 0);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+	})
+}
+
+#[test]
+fn rescue_tokens_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 1_000));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 100));
+
+		assert_noop!(
+			TokenFungible::rescue_tokens(Origin::signed(ALICE), 1, BOB, CHARLIE, 60),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		// no allowance is needed and no fee is taken
+		assert_ok!(TokenFungible::rescue_tokens(Origin::root(), 1, BOB, CHARLIE, 60));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Rescued(
+			1, BOB, CHARLIE, 60,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 40);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 60);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+
+		assert_noop!(
+			TokenFungible::rescue_tokens(Origin::root(), 1, BOB, CHARLIE, 41),
+			Error::<Test>::InsufficientTokens
+		);
+		assert_noop!(
+			TokenFungible::rescue_tokens(Origin::root(), 2, BOB, CHARLIE, 1),
+			Error::<Test>::InvalidId
+		);
+	})
+}
+
+#[test]
+fn max_allowance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+		assert_ok!(TokenFungible::set_max_allowance(Origin::signed(ALICE), 1, Some(100)));
+		assert_eq!(TokenFungible::max_allowance(1), Some(100));
+
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 60));
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 40));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 100);
+
+		assert_noop!(
+			TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 1),
+			Error::<Test>::AllowanceCapExceeded
+		);
+		assert_noop!(
+			TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 1),
+			Error::<Test>::AllowanceCapExceeded
+		);
+		assert_noop!(
+			TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 101),
+			Error::<Test>::AllowanceCapExceeded
+		);
+
+		// lowering the cap doesn't block decreasing an existing allowance
+		assert_ok!(TokenFungible::set_max_allowance(Origin::signed(ALICE), 1, Some(10)));
+		assert_ok!(TokenFungible::decrease_allowance(Origin::signed(ALICE), 1, BOB, 50));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 50);
+
+		assert_ok!(TokenFungible::set_max_allowance(Origin::signed(ALICE), 1, None));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 900));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 950);
+	})
+}
+
+#[test]
+fn max_allowance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenFungible::set_max_allowance(Origin::signed(ALICE), 1, Some(100)),
+			Error::<Test>::InvalidId
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::set_max_allowance(Origin::signed(BOB), 1, Some(100)),
+			Error::<Test>::NoPermission
+		);
+
+		// uncapped by default
+		assert_eq!(TokenFungible::max_allowance(1), None);
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, u128::MAX));
+	})
+}
+
+#[test]
+fn allowance_batch_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 50));
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, 4, 20));
+
+		assert_eq!(
+			TokenFungible::allowance_batch(1, &ALICE, vec![CHARLIE, BOB, 5, 4]),
+			vec![0, 50, 0, 20]
+		);
+		assert_eq!(TokenFungible::allowance_batch(1, &BOB, vec![ALICE]), vec![0]);
+
+		let spenders = (10..(10 + MAX_ALLOWANCE_BATCH as u64 + 5)).collect::<Vec<_>>();
+		assert_eq!(
+			TokenFungible::allowance_batch(1, &ALICE, spenders).len(),
+			MAX_ALLOWANCE_BATCH as usize
+		);
+	})
+}
+
+#[test]
+fn reflections_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 600));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 300));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 100));
+		assert_noop!(
+			TokenFungible::set_reflection_fee(Origin::signed(BOB), 1, 1_000),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::set_reflection_fee(Origin::signed(ALICE), 1, MAX_BPS + 1),
+			Error::<Test>::InvalidFee
+		);
+		assert_ok!(TokenFungible::set_reflection_fee(Origin::signed(ALICE), 1, 1_000));
+
+		// 10 is shared between the 990 left with ALICE, BOB and CHARLIE
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, 4, 100));
+		assert_eq!(TokenFungible::balance_of(1, 4), 90);
+		assert_eq!(TokenFungible::balance_of(1, TokenFungible::reflection_account_id()), 10);
+		assert_eq!(TokenFungible::claimable_reflections(1, &ALICE), 590 * 10 / 990);
+		assert_eq!(TokenFungible::claimable_reflections(1, &BOB), 300 * 10 / 990);
+		assert_eq!(TokenFungible::claimable_reflections(1, &CHARLIE), 100 * 10 / 990);
+		assert_eq!(TokenFungible::claimable_reflections(1, &4), 0);
+
+		// 10 more is shared between the 980 held outside the reflection account
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 100));
+		assert_eq!(
+			TokenFungible::claimable_reflections(1, &ALICE),
+			590 * 10 / 990 + 500 * 10 / 980
+		);
+		assert_eq!(TokenFungible::claimable_reflections(1, &BOB), 300 * 10 / 990 + 290 * 10 / 980);
+		assert_eq!(
+			TokenFungible::claimable_reflections(1, &CHARLIE),
+			(100 * 10 * 980 + 100 * 10 * 990) / (990 * 980)
+		);
+		assert_eq!(TokenFungible::claimable_reflections(1, &4), 90 * 10 / 980);
+
+		assert_ok!(TokenFungible::claim_reflections(Origin::signed(ALICE), 1));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 510);
+		assert_eq!(TokenFungible::claimable_reflections(1, &ALICE), 0);
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::ReflectionsClaimed(
+			1, ALICE, 10,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, TokenFungible::reflection_account_id()), 10);
+
+		// claiming again pays nothing
+		assert_ok!(TokenFungible::claim_reflections(Origin::signed(ALICE), 1));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 510);
+	})
+}
+
+#[test]
+fn validate_create_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::validate_create(b"Dollar".to_vec(), b"USD".to_vec(), 6));
+		assert_noop!(
+			TokenFungible::validate_create([0u8; 51].to_vec(), b"USD".to_vec(), 6),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			TokenFungible::validate_create(b"Dollar".to_vec(), [0u8; 51].to_vec(), 6),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(TokenFungible::validate_create(
+			b"Dollar".to_vec(),
+			b"USD".to_vec(),
+			MAX_DECIMALS
+		));
+		assert_noop!(
+			TokenFungible::validate_create(b"Dollar".to_vec(), b"USD".to_vec(), MAX_DECIMALS + 1),
+			Error::<Test>::InvalidDecimals
+		);
+
+		assert_ok!(TokenFungible::create_token_named(
+			Origin::signed(ALICE),
+			b"USD".to_vec(),
+			b"Dollar".to_vec(),
+			6
+		));
+		assert_noop!(
+			TokenFungible::validate_create(b"Dollar".to_vec(), b"USD".to_vec(), 6),
+			Error::<Test>::DuplicateSymbol
+		);
+
+		// nothing is created by validating
+		let next_id = NextTokenId::<Test>::get();
+		assert_ok!(TokenFungible::validate_create(b"Euro".to_vec(), b"EUR".to_vec(), 6));
+		assert_eq!(NextTokenId::<Test>::get(), next_id);
+		assert_eq!(TokenFungible::token_count(), 1);
+	})
+}
+
+#[test]
+fn peek_token_ids_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		let peeked = TokenFungible::peek_token_ids(3);
+		assert_eq!(peeked, vec![0, 2, 3]);
+		for id in peeked {
+			assert_ok!(TokenFungible::create_token_default(
+				Origin::signed(ALICE),
+				b"W3G".to_vec(),
+				b"W3G".to_vec()
+			));
+			assert!(TokenFungible::exists(id));
+		}
+		assert_eq!(TokenFungible::peek_token_ids(0), vec![]);
+		assert_eq!(TokenFungible::peek_token_ids(1000).len(), MAX_PEEK_TOKEN_IDS as usize);
+
+		// the list stops short of the id ceiling
+		NextTokenId::<Test>::put(u32::MAX - 2);
+		assert_eq!(TokenFungible::peek_token_ids(5), vec![u32::MAX - 2, u32::MAX - 1]);
+		assert_ok!(TokenFungible::create_token_default(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"W3G".to_vec()
+		));
+		assert_ok!(TokenFungible::create_token_default(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"W3G".to_vec()
+		));
+		assert!(TokenFungible::exists(u32::MAX - 1));
+		assert_noop!(
+			TokenFungible::create_token_default(
+				Origin::signed(ALICE),
+				b"W3G".to_vec(),
+				b"W3G".to_vec()
+			),
+			Error::<Test>::NoAvailableTokenId
+		);
+		assert_eq!(TokenFungible::peek_token_ids(5), vec![]);
+	})
+}
+
+#[test]
+fn transfer_policy_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+		assert_noop!(
+			TokenFungible::set_transfer_policy(Origin::signed(BOB), 1, TransferPolicy::Allowlist),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::set_account_listed(Origin::signed(BOB), 1, BOB, true),
+			Error::<Test>::NoPermission
+		);
+
+		// allowlist: both sides must be listed
+		assert_ok!(TokenFungible::set_transfer_policy(
+			Origin::signed(ALICE),
+			1,
+			TransferPolicy::Allowlist
+		));
+		assert_ok!(TokenFungible::set_account_listed(Origin::signed(ALICE), 1, ALICE, true));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 100),
+			Error::<Test>::TransferNotAllowed
+		);
+		assert_ok!(TokenFungible::set_account_listed(Origin::signed(ALICE), 1, BOB, true));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(BOB), 1, CHARLIE, 50));
+		assert_noop!(
+			TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, BOB, 4, 50),
+			Error::<Test>::TransferNotAllowed.with_weight(0)
+		);
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, BOB, ALICE, 50));
+
+		// blocklist: neither side may be listed
+		assert_ok!(TokenFungible::set_transfer_policy(
+			Origin::signed(ALICE),
+			1,
+			TransferPolicy::Blocklist
+		));
+		assert_ok!(TokenFungible::set_account_listed(Origin::signed(ALICE), 1, ALICE, false));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10),
+			Error::<Test>::TransferNotAllowed
+		);
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10),
+			Error::<Test>::TransferNotAllowed
+		);
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 10));
+
+		// governance rescues ignore the policy
+		assert_ok!(TokenFungible::rescue_tokens(Origin::root(), 1, BOB, CHARLIE, 50));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 60);
+
+		assert_ok!(TokenFungible::set_transfer_policy(
+			Origin::signed(ALICE),
+			1,
+			TransferPolicy::Open
+		));
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, BOB, 10));
+	})
+}
+
+#[test]
+fn balance_changed_events_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		let balance_changed = || {
+			System::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					Event::TokenFungible(TokenFungibleEvent::BalanceChanged(
+						id,
+						who,
+						before,
+						after,
+					)) => Some((id, who, before, after)),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// off unless configured
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(balance_changed(), vec![]);
+
+		EmitBalanceChanged::set(&true);
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(balance_changed(), vec![(1, ALICE, 100, 200)]);
+
+		System::reset_events();
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 30));
+		assert_eq!(balance_changed(), vec![(1, ALICE, 200, 170), (1, BOB, 0, 30)]);
+
+		System::reset_events();
+		assert_ok!(TokenFungible::burn(Origin::signed(BOB), 1, 10));
+		assert_eq!(balance_changed(), vec![(1, BOB, 30, 20)]);
+	})
+}
+
+#[test]
+fn create_token_weight_should_scale_with_metadata() {
+	use frame_support::weights::GetDispatchInfo;
+
+	let weight = |name: Vec<u8>, symbol: Vec<u8>| {
+		crate::Call::<Test>::create_token { id: 1, name, symbol, decimals: 18 }
+			.get_dispatch_info()
+			.weight
+	};
+
+	let minimal = weight(b"W".to_vec(), b"WG".to_vec());
+	let maximal = weight([b'W'; 50].to_vec(), [b'W'; 50].to_vec());
+	assert!(maximal > minimal);
+	assert!(weight([b'W'; 50].to_vec(), b"WG".to_vec()) > minimal);
+}
+
+#[test]
+fn error_code_should_work() {
+	let codes = [
+		(Error::<Test>::Unknown, 0),
+		(Error::<Test>::NoAvailableTokenId, 1),
+		(Error::<Test>::NumOverflow, 2),
+		(Error::<Test>::NoPermission, 3),
+		(Error::<Test>::NotOwner, 4),
+		(Error::<Test>::InvalidId, 5),
+		(Error::<Test>::AmountExceedAllowance, 6),
+		(Error::<Test>::BadMetadata, 7),
+		(Error::<Test>::InsufficientAuthorizedTokens, 8),
+		(Error::<Test>::InsufficientTokens, 9),
+		(Error::<Test>::ConfuseBehavior, 10),
+		(Error::<Test>::ApproveToCurrentOwner, 11),
+		(Error::<Test>::TooManyTransfers, 12),
+		(Error::<Test>::DuplicateSymbol, 13),
+		(Error::<Test>::InvalidFee, 14),
+		(Error::<Test>::SlippageExceeded, 15),
+		(Error::<Test>::MetadataFrozen, 16),
+		(Error::<Test>::TokenNotEmpty, 17),
+		(Error::<Test>::TokenStillActive, 18),
+		(Error::<Test>::AllowanceBelowZero, 19),
+		(Error::<Test>::NotOperator, 20),
+		(Error::<Test>::OperatorCapExceeded, 21),
+		(Error::<Test>::InvalidAccount, 22),
+		(Error::<Test>::AllowanceCapExceeded, 23),
+		(Error::<Test>::TransferNotAllowed, 24),
+		(Error::<Test>::IdInUse, 25),
+		(Error::<Test>::InvalidOrder, 26),
+		(Error::<Test>::UnknownOrder, 27),
+		(Error::<Test>::OrderOverfilled, 28),
+		(Error::<Test>::HolderLimitExceeded, 29),
+		(Error::<Test>::InvalidLottery, 30),
+		(Error::<Test>::TooManyHolders, 31),
+		(Error::<Test>::TransferCooldown, 32),
+		(Error::<Test>::MemoRequired, 33),
+		(Error::<Test>::MemoTooLong, 34),
+		(Error::<Test>::TooManyMints, 35),
+		(Error::<Test>::PalletAccountRecipient, 36),
+		(Error::<Test>::UnsafeRecipient, 37),
+		(Error::<Test>::InvalidSplit, 38),
+		(Error::<Test>::SupplyCapExceeded, 39),
+		(Error::<Test>::CapBelowSupply, 40),
+		(Error::<Test>::CapIncrease, 41),
+		(Error::<Test>::FractionalMintNotAllowed, 42),
+		(Error::<Test>::NoFallbackOwner, 43),
+		(Error::<Test>::OwnerStillActive, 44),
+		(Error::<Test>::TooManyApprovals, 45),
+		(Error::<Test>::TooManyEmissions, 46),
+		(Error::<Test>::AlreadyProcessed, 47),
+		(Error::<Test>::DecimalsMismatch, 48),
+		(Error::<Test>::UnknownStream, 49),
+		(Error::<Test>::InvalidStream, 50),
+		(Error::<Test>::NothingToWithdraw, 51),
+		(Error::<Test>::InvalidFeeSplit, 52),
+		(Error::<Test>::ExceedsMaxTx, 53),
+		(Error::<Test>::ExceedsMaxWallet, 54),
+		(Error::<Test>::InvalidDecimals, 55),
+	];
+	for (code, (err, expected)) in codes.iter().enumerate() {
+		assert_eq!(TokenFungible::error_code(err), *expected);
+		assert_eq!(code as u8, *expected);
+	}
+}
+
+#[test]
+fn transfer_all_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenFungible::transfer_all(Origin::signed(ALICE), 1, BOB, false),
+			Error::<Test>::InvalidId
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 50));
+
+		assert_ok!(TokenFungible::transfer_all(Origin::signed(ALICE), 1, CHARLIE, false));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 100);
+
+		// keep-alive leaves the minimum balance behind
+		assert_ok!(TokenFungible::transfer_all(Origin::signed(BOB), 1, CHARLIE, true));
+		assert_eq!(
+			TokenFungible::balance_of(1, BOB),
+			<TokenFungible as fungibles::Inspect<u64>>::minimum_balance(1)
+		);
+		assert_eq!(
+			TokenFungible::balance_of(1, CHARLIE),
+			150 - <TokenFungible as fungibles::Inspect<u64>>::minimum_balance(1)
+		);
+
+		// nothing left to send
+		System::reset_events();
+		assert_ok!(TokenFungible::transfer_all(Origin::signed(ALICE), 1, CHARLIE, false));
+		assert_eq!(System::events(), vec![]);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 150);
+	})
+}
+
+#[test]
+fn transfer_reducible_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_eq!(TokenFungible::transfer_reducible(1, &ALICE, &BOB, 30), Ok(30));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 30);
+
+		// more than the balance sends the balance
+		assert_eq!(TokenFungible::transfer_reducible(1, &ALICE, &BOB, 1_000), Ok(70));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+
+		assert_eq!(TokenFungible::transfer_reducible(1, &ALICE, &BOB, 1_000), Ok(0));
+
+		assert_ok!(TokenFungible::try_transfer(1, &BOB, &CHARLIE, 40));
+		assert_noop!(
+			TokenFungible::try_transfer(1, &BOB, &CHARLIE, 100),
+			Error::<Test>::InsufficientTokens
+		);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 40);
+	})
+}
+
+#[test]
+fn create_deterministic_should_work() {
+	new_test_ext().execute_with(|| {
+		let id = TokenFungible::deterministic_token_id(&ALICE, &[1u8; 32]);
+		assert_eq!(id, TokenFungible::deterministic_token_id(&ALICE, &[1u8; 32]));
+		assert_ne!(id, TokenFungible::deterministic_token_id(&ALICE, &[2u8; 32]));
+		assert_ne!(id, TokenFungible::deterministic_token_id(&BOB, &[1u8; 32]));
+
+		// the sequential counter is left alone
+		assert_ok!(TokenFungible::create_deterministic(
+			Origin::signed(ALICE),
+			[1u8; 32],
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert!(TokenFungible::exists(id));
+		assert_eq!(Tokens::<Test>::get(id).unwrap().owner, ALICE);
+		assert_eq!(NextTokenId::<Test>::get(), 0);
+
+		assert_noop!(
+			TokenFungible::create_deterministic(
+				Origin::signed(ALICE),
+				[1u8; 32],
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			),
+			Error::<Test>::IdInUse
+		);
+
+		let other = TokenFungible::deterministic_token_id(&BOB, &[1u8; 32]);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			other,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::create_deterministic(
+				Origin::signed(BOB),
+				[1u8; 32],
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			),
+			Error::<Test>::IdInUse
+		);
+	})
+}
+
+#[test]
+fn genesis_next_token_id_start_should_work() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(ALICE, 100 * DOLLARS)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	crate::GenesisConfig::<Test> { next_token_id_start: 1_000 }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(TokenFungible::peek_token_ids(1), vec![1_000]);
+
+		assert_ok!(TokenFungible::create_token_default(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"W3G".to_vec()
+		));
+		assert!(TokenFungible::exists(1_000));
+
+		// ids below the start stay free for explicit creation
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_eq!(TokenFungible::peek_token_ids(1), vec![1_001]);
+	})
+}
+
+#[test]
+fn limit_orders_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"USD".to_vec(),
+			b"USD".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 110));
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 2, BOB, 1000));
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 2, CHARLIE, 1000));
+		let escrow = TokenFungible::order_escrow_account_id();
+
+		assert_ok!(TokenFungible::place_order(Origin::signed(ALICE), 1, 100, 2, 300));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::OrderPlaced(
+			0, ALICE, 1, 100, 2, 300,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 10);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 100);
+
+		// partial fills by two takers at the order's price
+		assert_ok!(TokenFungible::fill_order(Origin::signed(BOB), 0, 30));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::OrderFilled(
+			0, BOB, 30, 90,
+		)));
+		assert_eq!(TokenFungible::order(0).unwrap().remaining, 70);
+		assert_ok!(TokenFungible::fill_order(Origin::signed(CHARLIE), 0, 70));
+		assert_eq!(TokenFungible::order(0), None);
+
+		assert_eq!(TokenFungible::balance_of(1, BOB), 30);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 70);
+		assert_eq!(TokenFungible::balance_of(2, ALICE), 300);
+		assert_eq!(TokenFungible::balance_of(2, BOB), 910);
+		assert_eq!(TokenFungible::balance_of(2, CHARLIE), 790);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 0);
+
+		// prices round up in favour of the maker, cancelling refunds what is left
+		assert_ok!(TokenFungible::place_order(Origin::signed(ALICE), 1, 10, 2, 3));
+		assert_ok!(TokenFungible::fill_order(Origin::signed(BOB), 1, 1));
+		assert_eq!(TokenFungible::balance_of(2, ALICE), 301);
+		assert_ok!(TokenFungible::cancel_order(Origin::signed(ALICE), 1));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::OrderCancelled(1, 9)));
+		assert_eq!(TokenFungible::order(1), None);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 9);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 0);
+	})
+}
+
+#[test]
+fn limit_orders_should_not_pay_fees_out_of_escrow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"USD".to_vec(),
+			b"USD".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 200));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, 4, 197));
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 2, CHARLIE, 300));
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 1_000));
+		assert_ok!(TokenFungible::set_reflection_fee(Origin::signed(ALICE), 1, 1_000));
+		let escrow = TokenFungible::order_escrow_account_id();
+		let pool = TokenFungible::reflection_account_id();
+
+		// the taker gets everything the order sold
+		assert_ok!(TokenFungible::place_order(Origin::signed(BOB), 1, 100, 2, 300));
+		assert_ok!(TokenFungible::fill_order(Origin::signed(CHARLIE), 0, 100));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 100);
+		assert_eq!(TokenFungible::balance_of(2, BOB), 300);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::balance_of(1, pool), 0);
+
+		// 9 of a transfer by 4 is shared over the 288 held outside the escrow
+		assert_ok!(TokenFungible::place_order(Origin::signed(BOB), 1, 100, 2, 300));
+		assert_ok!(TokenFungible::transfer(Origin::signed(4), 1, 5, 100));
+		assert_eq!(TokenFungible::balance_of(1, pool), 9);
+		assert_eq!(TokenFungible::claimable_reflections(1, &CHARLIE), 100 * 9 / 288);
+		assert_eq!(TokenFungible::claimable_reflections(1, &escrow), 0);
+
+		// nothing accrued in escrow comes back with the tokens
+		assert_ok!(TokenFungible::cancel_order(Origin::signed(BOB), 1));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+		assert_eq!(TokenFungible::claimable_reflections(1, &BOB), 0);
+	})
+}
+
+#[test]
+fn limit_orders_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"USD".to_vec(),
+			b"USD".to_vec(),
+			6
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 2, BOB, 10));
+
+		assert_noop!(
+			TokenFungible::place_order(Origin::signed(ALICE), 1, 100, 3, 300),
+			Error::<Test>::InvalidId
+		);
+		assert_noop!(
+			TokenFungible::place_order(Origin::signed(ALICE), 1, 100, 1, 300),
+			Error::<Test>::InvalidOrder
+		);
+		assert_noop!(
+			TokenFungible::place_order(Origin::signed(ALICE), 1, 0, 2, 300),
+			Error::<Test>::InvalidOrder
+		);
+		assert_noop!(
+			TokenFungible::place_order(Origin::signed(ALICE), 1, 101, 2, 300),
+			Error::<Test>::InsufficientTokens
+		);
+
+		assert_ok!(TokenFungible::place_order(Origin::signed(ALICE), 1, 100, 2, 300));
+		assert_noop!(
+			TokenFungible::fill_order(Origin::signed(BOB), 1, 10),
+			Error::<Test>::UnknownOrder
+		);
+		assert_noop!(
+			TokenFungible::fill_order(Origin::signed(ALICE), 0, 10),
+			Error::<Test>::ConfuseBehavior
+		);
+		assert_noop!(
+			TokenFungible::fill_order(Origin::signed(BOB), 0, 0),
+			Error::<Test>::InvalidOrder
+		);
+		assert_noop!(
+			TokenFungible::fill_order(Origin::signed(BOB), 0, 101),
+			Error::<Test>::OrderOverfilled
+		);
+		// 10 of the sell side costs 30
+		assert_noop!(
+			TokenFungible::fill_order(Origin::signed(BOB), 0, 10),
+			Error::<Test>::InsufficientTokens
+		);
+		assert_noop!(
+			TokenFungible::cancel_order(Origin::signed(BOB), 0),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::cancel_order(Origin::signed(ALICE), 1),
+			Error::<Test>::UnknownOrder
+		);
+	})
+}
+
+#[test]
+fn max_holders_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::set_max_holders(Origin::signed(BOB), 1, Some(2)),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_max_holders(Origin::signed(ALICE), 1, Some(2)));
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 10));
+		assert_eq!(TokenFungible::holder_count(1), 2);
+
+		// full: no new holders
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 10),
+			Error::<Test>::HolderLimitExceeded
+		);
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 10),
+			Error::<Test>::HolderLimitExceeded
+		);
+
+		// existing holders still trade, and leaving frees a slot
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 20));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 20);
+		assert_eq!(TokenFungible::holder_count(1), 2);
+
+		assert_ok!(TokenFungible::set_max_holders(Origin::signed(ALICE), 1, None));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		assert_eq!(TokenFungible::holder_count(1), 3);
+	})
+}
+
+#[test]
+fn supply_threshold_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::set_supply_threshold(Origin::signed(BOB), 1, Some(100)),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_supply_threshold(Origin::signed(ALICE), 1, Some(100)));
+
+		let reached = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						Event::TokenFungible(TokenFungibleEvent::SupplyThresholdReached(1, 100))
+					)
+				})
+				.count()
+		};
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 60));
+		assert_eq!(reached(), 0);
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 60));
+		System::assert_last_event(Event::TokenFungible(
+			TokenFungibleEvent::SupplyThresholdReached(1, 100),
+		));
+		assert!(TokenFungible::supply_threshold_reached(1));
+
+		// fires once, even after burning back below and minting past it again
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 10));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(reached(), 1);
+
+		// setting it again re-arms it
+		assert_ok!(TokenFungible::set_supply_threshold(Origin::signed(ALICE), 1, Some(100)));
+		assert!(!TokenFungible::supply_threshold_reached(1));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1));
+		assert_eq!(reached(), 2);
+
+		assert_ok!(TokenFungible::set_supply_threshold(Origin::signed(ALICE), 1, None));
+		assert_eq!(TokenFungible::supply_threshold(1), None);
+	})
+}
+
+#[test]
+fn lottery_distribute_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 100));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 300));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, 4, 600));
+		// pallet accounts and emptied balances are skipped
+		Balances::<Test>::insert(1, TokenFungible::reflection_account_id(), 10_000);
+		Balances::<Test>::insert(1, 5, 0);
+
+		// holders [BOB: 100, CHARLIE: 300, 4: 600]: 150 lands on CHARLIE, then 750 % 700 on BOB
+		RandomDraws::set(&vec![150, 750]);
+		assert_ok!(TokenFungible::lottery_distribute(Origin::signed(ALICE), 1, 101, 2));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::LotteryDistributed(
+			1,
+			vec![CHARLIE, BOB],
+			50,
+		)));
+
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 900);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 150);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 350);
+		assert_eq!(TokenFungible::balance_of(1, 4), 600);
+		assert_eq!(TokenFungible::total_supply(1), 2000);
+	})
+}
+
+#[test]
+fn lottery_distribute_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 100));
+
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(BOB), 1, 10, 1),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(ALICE), 1, 10, 0),
+			Error::<Test>::InvalidLottery
+		);
+		// the owner never counts as a holder
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(ALICE), 1, 10, 2),
+			Error::<Test>::InvalidLottery
+		);
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(ALICE), 2, 10, 1),
+			Error::<Test>::InvalidId
+		);
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(ALICE), 1, 2000, 1),
+			Error::<Test>::InsufficientTokens
+		);
+
+		// emptied balances still count against the holder bound
+		for account in 100..200 {
+			Balances::<Test>::insert(1, account, 0);
+		}
+		assert_noop!(
+			TokenFungible::lottery_distribute(Origin::signed(ALICE), 1, 10, 1),
+			Error::<Test>::TooManyHolders
+		);
+	})
+}
+
+#[test]
+fn transfer_cooldown_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 100));
+		assert_noop!(
+			TokenFungible::set_transfer_cooldown(Origin::signed(BOB), 1, Some(3)),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_transfer_cooldown(Origin::signed(ALICE), 1, Some(3)));
+
+		// back to back transfers
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10));
+		assert_eq!(TokenFungible::last_transfer(1, BOB), Some(1));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10),
+			Error::<Test>::TransferCooldown
+		);
+		// the recipient isn't held back
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, BOB, 5));
+
+		System::set_block_number(3);
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10),
+			Error::<Test>::TransferCooldown
+		);
+		System::set_block_number(4);
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10));
+		assert_eq!(TokenFungible::last_transfer(1, BOB), Some(4));
+
+		// an aggregated batch starts the cooldown like any other transfer
+		System::set_block_number(7);
+		assert_ok!(TokenFungible::transfer_batch(
+			Origin::signed(BOB),
+			1,
+			vec![(CHARLIE, 10)],
+			true
+		));
+		assert_eq!(TokenFungible::last_transfer(1, BOB), Some(7));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10),
+			Error::<Test>::TransferCooldown
+		);
+
+		// lifted by the owner after launch
+		assert_ok!(TokenFungible::set_transfer_cooldown(Origin::signed(ALICE), 1, None));
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10));
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 10));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 55);
+	})
+}
+
+#[test]
+fn unbalanced_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		// The supply is left to `set_total_issuance`.
+		assert_ok!(<TokenFungible as fungibles::Unbalanced<u64>>::set_balance(1, &BOB, 50));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 50);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+		assert_eq!(TokenFungible::holder_count(1), 2);
+
+		assert_ok!(<TokenFungible as fungibles::Unbalanced<u64>>::set_balance(1, &ALICE, 40));
+		assert_eq!(TokenFungible::total_supply(1), 100);
+
+		assert_eq!(
+			<TokenFungible as fungibles::Unbalanced<u64>>::decrease_balance(1, &BOB, 50),
+			Ok(50)
+		);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+		assert_eq!(TokenFungible::holder_count(1), 1);
+
+		assert_noop!(
+			<TokenFungible as fungibles::Unbalanced<u64>>::set_balance(2, &BOB, 50),
+			Error::<Test>::InvalidId
+		);
+
+		<TokenFungible as fungibles::Unbalanced<u64>>::set_total_issuance(1, 1000);
+		assert_eq!(TokenFungible::total_supply(1), 1000);
+		assert_eq!(TokenFungible::total_minted(1), 1000);
+		<TokenFungible as fungibles::Unbalanced<u64>>::set_total_issuance(1, 400);
+		assert_eq!(TokenFungible::total_burned(1), 600);
+	})
+}
+
+#[test]
+fn balanced_should_keep_supply_at_sum_of_balances() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		let sum_of_balances = || {
+			crate::Balances::<Test>::iter_prefix(1)
+				.map(|(_, balance)| balance)
+				.sum::<Balance>()
+		};
+
+		// Dropping the imbalances settles the supply.
+		drop(<TokenFungible as fungibles::Balanced<u64>>::deposit(1, &BOB, 50));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 50);
+		assert_eq!(TokenFungible::total_supply(1), 150);
+		assert_eq!(TokenFungible::total_supply(1), sum_of_balances());
+
+		drop(<TokenFungible as fungibles::Balanced<u64>>::withdraw(1, &ALICE, 30));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 70);
+		assert_eq!(TokenFungible::total_supply(1), 120);
+		assert_eq!(TokenFungible::total_supply(1), sum_of_balances());
+		assert_eq!(
+			TokenFungible::total_minted(1) - TokenFungible::total_burned(1),
+			TokenFungible::total_supply(1)
+		);
+
+		// Deposits are held to the max wallet like mints.
+		assert_ok!(TokenFungible::set_max_wallet(Origin::signed(ALICE), 1, Some(60)));
+		assert_noop!(
+			<TokenFungible as fungibles::Balanced<u64>>::deposit(1, &BOB, 20).map(drop),
+			Error::<Test>::ExceedsMaxWallet
+		);
+	})
+}
+
+#[test]
+fn fresh_recipient_account_should_be_provided() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert!(!System::account_exists(&CHARLIE));
+
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 30));
+		assert!(System::account_exists(&CHARLIE));
+		assert_eq!(System::providers(&CHARLIE), 1);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 30);
+		// existing accounts get no extra reference
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 30));
+		assert_eq!(System::providers(&BOB), 1);
+
+		// still holding, it stays provided
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, BOB, 10));
+		assert_eq!(System::providers(&CHARLIE), 1);
+
+		// emptied out, the account is reaped
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, BOB, 20));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 0);
+		assert!(!System::account_exists(&CHARLIE));
+		assert_eq!(System::providers(&BOB), 1);
+	})
+}
+
+#[test]
+fn require_memo_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		// unset: both work
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		assert_ok!(TokenFungible::transfer_with_memo(
+			Origin::signed(ALICE),
+			1,
+			BOB,
+			10,
+			b"deposit-42".to_vec()
+		));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::TransferMemo(
+			1,
+			ALICE,
+			BOB,
+			b"deposit-42".to_vec(),
+		)));
+
+		assert_noop!(
+			TokenFungible::set_require_memo(Origin::signed(BOB), 1, true),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_require_memo(Origin::signed(ALICE), 1, true));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10),
+			Error::<Test>::MemoRequired
+		);
+		assert_noop!(
+			TokenFungible::transfer_all(Origin::signed(ALICE), 1, BOB, false),
+			Error::<Test>::MemoRequired
+		);
+		assert_ok!(TokenFungible::transfer_with_memo(
+			Origin::signed(ALICE),
+			1,
+			BOB,
+			10,
+			b"deposit-43".to_vec()
+		));
+		assert_noop!(
+			TokenFungible::transfer_with_memo(Origin::signed(ALICE), 1, BOB, 10, vec![0; 51]),
+			Error::<Test>::MemoTooLong
+		);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 30);
+	})
+}
+
+#[test]
+fn mint_batch_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_ok!(TokenFungible::mint_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 10), (CHARLIE, 20), (BOB, 5)]
+		));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 15);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 20);
+		assert_eq!(TokenFungible::total_supply(1), 135);
+
+		let free = <Test as Config>::Currency::free_balance(&ALICE);
+		MintFee::set(&(10 * CENTS));
+		assert_ok!(TokenFungible::mint_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 1), (CHARLIE, 1)]
+		));
+		assert_eq!(<Test as Config>::Currency::free_balance(&ALICE), free - 20 * CENTS);
+	})
+}
+
+#[test]
+fn mint_batch_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::mint_batch(Origin::signed(ALICE), 1, vec![(BOB, 1); 11]),
+			Error::<Test>::TooManyMints
+		);
+		assert_noop!(
+			TokenFungible::mint_batch(Origin::signed(BOB), 1, vec![(BOB, 1)]),
+			Error::<Test>::NoPermission
+		);
+		// the second entry takes the supply past `Balance::MAX`: nothing is minted
+		assert_noop!(
+			TokenFungible::mint_batch(
+				Origin::signed(ALICE),
+				1,
+				vec![(BOB, 10), (CHARLIE, Balance::MAX - 50)]
+			),
+			Error::<Test>::NumOverflow
+		);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+	})
+}
+
+#[test]
+fn safe_transfer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		UnsafeRecipients::set(&vec![CHARLIE]);
+
+		assert_noop!(
+			TokenFungible::safe_transfer(Origin::signed(ALICE), 1, 0, 10),
+			Error::<Test>::InvalidAccount
+		);
+		for pallet_account in [
+			TokenFungible::reflection_account_id(),
+			TokenFungible::treasury_account_id(),
+			TokenFungible::order_escrow_account_id(),
+		] {
+			assert_noop!(
+				TokenFungible::safe_transfer(Origin::signed(ALICE), 1, pallet_account, 10),
+				Error::<Test>::PalletAccountRecipient
+			);
+		}
+		assert_noop!(
+			TokenFungible::safe_transfer(Origin::signed(ALICE), 1, CHARLIE, 10),
+			Error::<Test>::UnsafeRecipient
+		);
+		// plain transfers don't check
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 10));
+
+		assert_ok!(TokenFungible::safe_transfer(Origin::signed(ALICE), 1, BOB, 10));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 10);
+	})
+}
+
+#[test]
+fn transfer_split_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+
+		// a third each of 100 rounds down to 33, the last recipient gets the leftover
+		let third = Perbill::from_parts(333_333_333);
+		assert_ok!(TokenFungible::transfer_split(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, third), (CHARLIE, third), (4, Perbill::from_parts(333_333_334))],
+			100
+		));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 33);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 33);
+		assert_eq!(TokenFungible::balance_of(1, 4), 34);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 900);
+	})
+}
+
+#[test]
+fn transfer_split_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+
+		assert_noop!(
+			TokenFungible::transfer_split(
+				Origin::signed(ALICE),
+				1,
+				vec![(BOB, Perbill::from_percent(50)), (CHARLIE, Perbill::from_percent(40))],
+				100
+			),
+			Error::<Test>::InvalidSplit
+		);
+		assert_noop!(
+			TokenFungible::transfer_split(
+				Origin::signed(ALICE),
+				1,
+				vec![(BOB, Perbill::from_percent(60)), (CHARLIE, Perbill::from_percent(60))],
+				100
+			),
+			Error::<Test>::InvalidSplit
+		);
+		assert_noop!(
+			TokenFungible::transfer_split(Origin::signed(ALICE), 1, vec![], 100),
+			Error::<Test>::InvalidSplit
+		);
+		assert_noop!(
+			TokenFungible::transfer_split(
+				Origin::signed(ALICE),
+				1,
+				vec![(BOB, Perbill::from_percent(10)); 11],
+				100
+			),
+			Error::<Test>::TooManyTransfers
+		);
+	})
+}
+
+#[test]
+fn min_metadata_length_should_work() {
+	new_test_ext().execute_with(|| {
+		// name: at least 1 byte
+		assert_noop!(
+			TokenFungible::create_token(
+				Origin::signed(ALICE),
+				1,
+				b"".to_vec(),
+				b"W3G".to_vec(),
+				18
+			),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		// symbol: at least 2 bytes
+		assert_noop!(
+			TokenFungible::create_token(
+				Origin::signed(ALICE),
+				2,
+				b"W3G".to_vec(),
+				b"W".to_vec(),
+				18
+			),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			2,
+			b"W3G".to_vec(),
+			b"WG".to_vec(),
+			18
+		));
+
+		// updates are checked the same way
+		assert_noop!(
+			TokenFungible::set_metadata(Origin::signed(ALICE), 1, b"".to_vec(), b"W3G".to_vec()),
+			Error::<Test>::BadMetadata
+		);
+		assert_noop!(
+			TokenFungible::set_metadata(Origin::signed(ALICE), 1, b"W3G".to_vec(), b"W".to_vec()),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(TokenFungible::set_metadata(
+			Origin::signed(ALICE),
+			1,
+			b"W".to_vec(),
+			b"WG".to_vec()
+		));
+	})
+}
+
+#[test]
+fn reduce_cap_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::reduce_cap(Origin::signed(BOB), 1, 1000),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 1000));
+		assert_ok!(TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 150));
+		assert_noop!(
+			TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 99),
+			Error::<Test>::CapBelowSupply
+		);
+		assert_noop!(
+			TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 151),
+			Error::<Test>::CapIncrease
+		);
+
+		// minting stops at the cap
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 50));
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1),
+			Error::<Test>::SupplyCapExceeded
+		);
+		assert_ok!(TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 150));
+		assert_eq!(TokenFungible::token_info(1).unwrap().max_supply, Some(150));
+	})
+}
+
+#[test]
+fn total_minted_and_burned_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		let consistent = || {
+			TokenFungible::total_minted(1) - TokenFungible::total_burned(1) ==
+				TokenFungible::total_supply(1)
+		};
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert!(consistent());
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 30));
+		assert!(consistent());
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 50));
+		assert_ok!(TokenFungible::burn(Origin::signed(BOB), 1, 50));
+		assert!(consistent());
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 20));
+		assert!(consistent());
+
+		assert_eq!(TokenFungible::total_minted(1), 150);
+		assert_eq!(TokenFungible::total_burned(1), 80);
+		assert_eq!(TokenFungible::total_supply(1), 70);
+	})
+}
+
+#[test]
+fn whole_units_only_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			2
+		));
+
+		// off: any amount
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 150));
+
+		assert_noop!(
+			TokenFungible::set_whole_units_only(Origin::signed(BOB), 1, true),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_whole_units_only(Origin::signed(ALICE), 1, true));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 300));
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 150),
+			Error::<Test>::FractionalMintNotAllowed
+		);
+		assert_noop!(
+			TokenFungible::mint_batch(Origin::signed(ALICE), 1, vec![(BOB, 100), (CHARLIE, 1)]),
+			Error::<Test>::FractionalMintNotAllowed
+		);
+
+		assert_ok!(TokenFungible::set_whole_units_only(Origin::signed(ALICE), 1, false));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1));
+		assert_eq!(TokenFungible::total_supply(1), 451);
+	})
+}
+
+#[test]
+fn fallback_owner_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_noop!(
+			TokenFungible::trigger_fallback(Origin::signed(CHARLIE), 1),
+			Error::<Test>::NoFallbackOwner
+		);
+		assert_noop!(
+			TokenFungible::set_fallback_owner(Origin::signed(BOB), 1, Some(BOB), 10),
+			Error::<Test>::NoPermission
+		);
+
+		System::set_block_number(5);
+		assert_ok!(TokenFungible::set_fallback_owner(Origin::signed(ALICE), 1, Some(BOB), 10));
+		assert_eq!(TokenFungible::fallback_owner(1), Some((BOB, 10)));
+		assert_eq!(TokenFungible::last_owner_action(1), 5);
+
+		// within the window
+		System::set_block_number(15);
+		assert_noop!(
+			TokenFungible::trigger_fallback(Origin::signed(CHARLIE), 1),
+			Error::<Test>::OwnerStillActive
+		);
+
+		// an owner action restarts the wait
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(TokenFungible::last_owner_action(1), 15);
+		System::set_block_number(25);
+		assert_noop!(
+			TokenFungible::trigger_fallback(Origin::signed(CHARLIE), 1),
+			Error::<Test>::OwnerStillActive
+		);
+
+		// beyond the window
+		System::set_block_number(26);
+		assert_ok!(TokenFungible::trigger_fallback(Origin::signed(CHARLIE), 1));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::FallbackTriggered(
+			1, ALICE, BOB,
+		)));
+		assert_eq!(TokenFungible::fallback_owner(1), None);
+
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::mint(Origin::signed(BOB), 1, BOB, 100));
+		assert_noop!(
+			TokenFungible::trigger_fallback(Origin::signed(CHARLIE), 1),
+			Error::<Test>::NoFallbackOwner
+		);
+	})
+}
+
+#[test]
+fn max_approvals_per_owner_should_work() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalsPerOwner::set(&Some(2));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 10));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 10));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 2);
+		assert_noop!(
+			TokenFungible::approve(Origin::signed(ALICE), 1, 4, 10),
+			Error::<Test>::TooManyApprovals
+		);
+
+		// topping up an existing approval doesn't take a slot
+		assert_ok!(TokenFungible::increase_allowance(Origin::signed(ALICE), 1, BOB, 5));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 2);
+
+		// spending an allowance to zero frees its slot
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, ALICE, BOB, 10));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 1);
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, 4, 10));
+
+		// and so does revoking one
+		assert_ok!(TokenFungible::decrease_allowance(Origin::signed(ALICE), 1, BOB, 15));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 1);
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, 5, 10));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 2);
+
+		MaxApprovalsPerOwner::set(&None);
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, 6, 10));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 3);
+	})
+}
+
+#[test]
+fn mint_and_approve_should_respect_max_approvals() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalsPerOwner::set(&Some(1));
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_ok!(TokenFungible::mint_and_approve(
+			Origin::signed(ALICE),
+			1,
+			BOB,
+			100,
+			CHARLIE,
+			50
+		));
+		assert_eq!(TokenFungible::approvals_count(1, BOB), 1);
+
+		// the mint is rolled back together with the rejected approval
+		assert_noop!(
+			TokenFungible::mint_and_approve(Origin::signed(ALICE), 1, BOB, 100, 4, 50),
+			Error::<Test>::TooManyApprovals
+		);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+		assert_eq!(TokenFungible::allowances(1, (BOB, 4)), 0);
+
+		MaxApprovalsPerOwner::set(&None);
+	})
+}
+
+#[test]
+fn emission_should_work() {
+	use frame_support::traits::Hooks;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::reduce_cap(Origin::signed(ALICE), 1, 250));
+		assert_noop!(
+			TokenFungible::set_emission(Origin::signed(BOB), 1, 100, BOB),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_emission(Origin::signed(ALICE), 1, 100, BOB));
+		assert_eq!(TokenFungible::emission(1), Some((BOB, 100)));
+
+		TokenFungible::on_initialize(2);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+		TokenFungible::on_initialize(3);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 200);
+		assert_eq!(TokenFungible::total_supply(1), 200);
+
+		// only what is left under the cap
+		TokenFungible::on_initialize(4);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 250);
+		assert_eq!(TokenFungible::total_supply(1), 250);
+
+		// and the schedule ends there
+		TokenFungible::on_initialize(5);
+		assert_eq!(TokenFungible::total_supply(1), 250);
+		assert_eq!(TokenFungible::emission(1), None);
+	})
+}
+
+#[test]
+fn emission_should_be_bounded() {
+	use frame_support::traits::Hooks;
+
+	new_test_ext().execute_with(|| {
+		for id in 1..=11 {
+			assert_ok!(TokenFungible::create_token(
+				Origin::signed(ALICE),
+				id,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				18
+			));
+		}
+		for id in 1..=10 {
+			assert_ok!(TokenFungible::set_emission(Origin::signed(ALICE), id, 1, BOB));
+		}
+		assert_noop!(
+			TokenFungible::set_emission(Origin::signed(ALICE), 11, 1, BOB),
+			Error::<Test>::TooManyEmissions
+		);
+
+		// changing an existing schedule doesn't take a slot
+		assert_ok!(TokenFungible::set_emission(Origin::signed(ALICE), 1, 2, CHARLIE));
+
+		assert_ok!(TokenFungible::set_emission(Origin::signed(ALICE), 2, 0, BOB));
+		assert_ok!(TokenFungible::set_emission(Origin::signed(ALICE), 11, 1, BOB));
+
+		TokenFungible::on_initialize(2);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 2);
+		assert_eq!(TokenFungible::balance_of(2, BOB), 0);
+		assert_eq!(TokenFungible::balance_of(11, BOB), 1);
+	})
+}
+
+#[test]
+fn total_approved_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, Balance::MAX));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 0);
+		assert_eq!(TokenFungible::total_approved(1, ALICE), 0);
+
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 10));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 20));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, 4, 30));
+		// allowances granted by others don't count
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(BOB), 1, CHARLIE, 40));
+
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 3);
+		assert_eq!(TokenFungible::total_approved(1, ALICE), 60);
+		assert_eq!(TokenFungible::approvals_count(1, BOB), 1);
+		assert_eq!(TokenFungible::total_approved(1, BOB), 40);
+
+		// the total doesn't depend on the approval count being right
+		ApprovalCounts::<Test>::remove(1, BOB);
+		assert_eq!(TokenFungible::total_approved(1, BOB), 40);
+
+		// an unlimited allowance makes the total unlimited
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, 5, Balance::MAX - 100));
+		assert_eq!(TokenFungible::approvals_count(1, ALICE), 4);
+		assert_eq!(TokenFungible::total_approved(1, ALICE), Balance::MAX);
+	})
+}
+
+#[test]
+fn bridge_mint_should_work() {
+	new_test_ext().execute_with(|| {
+		let external_ref = H256::repeat_byte(1);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_noop!(
+			TokenFungible::bridge_mint(Origin::signed(ALICE), 1, BOB, 100, external_ref),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(TokenFungible::bridge_mint(Origin::root(), 1, BOB, 100, external_ref));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::BridgeMinted(
+			1,
+			BOB,
+			100,
+			external_ref,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+		assert!(TokenFungible::bridge_mint_processed(1, external_ref));
+
+		assert_noop!(
+			TokenFungible::bridge_mint(Origin::root(), 1, BOB, 100, external_ref),
+			Error::<Test>::AlreadyProcessed
+		);
+
+		assert_ok!(TokenFungible::bridge_mint(Origin::root(), 1, BOB, 50, H256::repeat_byte(2)));
+		assert_eq!(TokenFungible::total_supply(1), 150);
+	})
+}
+
+#[test]
+fn assert_same_decimals_should_work() {
+	new_test_ext().execute_with(|| {
+		for (id, decimals) in [(1, 18), (2, 18), (3, 6)] {
+			assert_ok!(TokenFungible::create_token(
+				Origin::signed(ALICE),
+				id,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				decimals
+			));
+		}
+
+		assert_ok!(TokenFungible::assert_same_decimals(1, 2));
+		assert_ok!(TokenFungible::assert_same_decimals(1, 1));
+		assert_noop!(TokenFungible::assert_same_decimals(1, 3), Error::<Test>::DecimalsMismatch);
+		assert_noop!(TokenFungible::assert_same_decimals(3, 2), Error::<Test>::DecimalsMismatch);
+		assert_noop!(TokenFungible::assert_same_decimals(1, 4), Error::<Test>::InvalidId);
+	})
+}
+
+#[test]
+fn streams_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 2000));
+		let escrow = TokenFungible::stream_escrow_account_id();
+
+		System::set_block_number(5);
+		assert_ok!(TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 1000, 10, 20));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::StreamCreated(
+			0, 1, ALICE, BOB, 1000, 10, 20,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 1000);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 1000);
+
+		// nothing before the start
+		assert_noop!(
+			TokenFungible::withdraw_from_stream(Origin::signed(BOB), 0),
+			Error::<Test>::NothingToWithdraw
+		);
+
+		// mid-stream
+		System::set_block_number(13);
+		assert_ok!(TokenFungible::withdraw_from_stream(Origin::signed(BOB), 0));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::StreamWithdrawn(
+			0, 300,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 300);
+		assert_eq!(TokenFungible::stream(0).unwrap().withdrawn, 300);
+		assert_noop!(
+			TokenFungible::withdraw_from_stream(Origin::signed(BOB), 0),
+			Error::<Test>::NothingToWithdraw
+		);
+
+		// after the end, the rest and the stream is gone
+		System::set_block_number(30);
+		assert_ok!(TokenFungible::withdraw_from_stream(Origin::signed(BOB), 0));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 1000);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 0);
+		assert_eq!(TokenFungible::stream(0), None);
+
+		// cancelling splits vested and unvested funds
+		assert_ok!(TokenFungible::create_stream(Origin::signed(ALICE), 1, CHARLIE, 1000, 30, 40));
+		System::set_block_number(32);
+		assert_ok!(TokenFungible::withdraw_from_stream(Origin::signed(CHARLIE), 1));
+		System::set_block_number(36);
+		assert_ok!(TokenFungible::cancel_stream(Origin::signed(ALICE), 1));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::StreamCancelled(
+			1, 400, 400,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 600);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 400);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 0);
+		assert_eq!(TokenFungible::stream(1), None);
+	})
+}
+
+#[test]
+fn streams_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		assert_noop!(
+			TokenFungible::create_stream(Origin::signed(ALICE), 2, BOB, 100, 10, 20),
+			Error::<Test>::InvalidId
+		);
+		assert_noop!(
+			TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 0, 10, 20),
+			Error::<Test>::InvalidStream
+		);
+		assert_noop!(
+			TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 100, 20, 20),
+			Error::<Test>::InvalidStream
+		);
+		assert_noop!(
+			TokenFungible::create_stream(Origin::signed(ALICE), 1, ALICE, 100, 10, 20),
+			Error::<Test>::InvalidStream
+		);
+		assert_noop!(
+			TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 101, 10, 20),
+			Error::<Test>::InsufficientTokens
+		);
+
+		assert_ok!(TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 100, 10, 20));
+		System::set_block_number(15);
+		assert_noop!(
+			TokenFungible::withdraw_from_stream(Origin::signed(BOB), 1),
+			Error::<Test>::UnknownStream
+		);
+		assert_noop!(
+			TokenFungible::withdraw_from_stream(Origin::signed(ALICE), 0),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::cancel_stream(Origin::signed(BOB), 0),
+			Error::<Test>::NoPermission
+		);
+	})
+}
+
+#[test]
+fn approve_once_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+
+		assert_ok!(TokenFungible::approve_once(Origin::signed(ALICE), 1, BOB, 100));
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 100));
+		assert!(TokenFungible::is_single_use_approval(1, (ALICE, BOB)));
+		assert!(!TokenFungible::is_single_use_approval(1, (ALICE, CHARLIE)));
+
+		// partially spent, the single-use approval is gone
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 30));
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Approval(
+			1, ALICE, BOB, 0,
+		)));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 0);
+		assert!(!TokenFungible::is_single_use_approval(1, (ALICE, BOB)));
+		assert_noop!(
+			TokenFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 30),
+			Error::<Test>::InsufficientAuthorizedTokens.with_weight(0)
+		);
+
+		// while a normal approval keeps its remainder
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, ALICE, BOB, 30));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, CHARLIE)), 70);
+		assert_ok!(TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, ALICE, BOB, 30));
+		assert_eq!(TokenFungible::allowances(1, (ALICE, CHARLIE)), 40);
+
+		assert_eq!(TokenFungible::balance_of(1, BOB), 60);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 30);
+	})
+}
+
+#[test]
+fn emit_transfer_events_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert!(Tokens::<Test>::get(1).unwrap().emit_transfer_events);
+
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		let seq = TokenFungible::event_seq();
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, ALICE, BOB, 10, seq,
+		)));
+
+		assert_noop!(
+			TokenFungible::set_emit_transfer_events(Origin::signed(BOB), 1, false),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_emit_transfer_events(Origin::signed(ALICE), 1, false));
+
+		let events = System::events().len();
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 10));
+		assert_eq!(System::events().len(), events);
+		assert_eq!(TokenFungible::event_seq(), seq);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 80);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 20);
+	})
+}
+
+#[test]
+fn total_supply_should_match_balances() {
+	new_test_ext().execute_with(|| {
+		let holders_total =
+			|| Balances::<Test>::iter_prefix(1).map(|(_, balance)| balance).sum::<Balance>();
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_ok!(TokenFungible::mint_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 30), (ALICE, 5)]
+		));
+		assert_eq!(TokenFungible::total_supply(1), 135);
+		assert_eq!(TokenFungible::total_supply(1), holders_total());
+
+		assert_ok!(TokenFungible::burn(Origin::signed(BOB), 1, 10));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 105));
+		assert_eq!(TokenFungible::total_supply(1), 20);
+		assert_eq!(TokenFungible::total_supply(1), holders_total());
+
+		// a failed mint or burn leaves both untouched
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, Balance::MAX),
+			Error::<Test>::NumOverflow
+		);
+		assert_noop!(TokenFungible::burn(Origin::signed(BOB), 1, 21), Error::<Test>::NumOverflow);
+		assert_eq!(TokenFungible::total_supply(1), holders_total());
+	})
+}
+
+#[test]
+fn total_supply_should_match_balances_when_hook_reenters() {
+	new_test_ext().execute_with(|| {
+		let holders_total =
+			|| Balances::<Test>::iter_prefix(1).map(|(_, balance)| balance).sum::<Balance>();
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+
+		// the hook moves part of the mint on before the supply is updated
+		Reentry::set(&Some((ALICE, BOB, 40)));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+		assert_eq!(Reentry::get(), None);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 60);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 40);
+		assert_eq!(TokenFungible::total_supply(1), 100);
+		assert_eq!(TokenFungible::total_supply(1), holders_total());
+
+		// and tops up the burner before the supply is updated
+		Reentry::set(&Some((BOB, ALICE, 10)));
+		assert_ok!(TokenFungible::burn(Origin::signed(ALICE), 1, 50));
+		assert_eq!(Reentry::get(), None);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 20);
+		assert_eq!(TokenFungible::balance_of(1, BOB), 30);
+		assert_eq!(TokenFungible::total_supply(1), 50);
+		assert_eq!(TokenFungible::total_supply(1), holders_total());
+		assert_eq!(
+			TokenFungible::total_minted(1) - TokenFungible::total_burned(1),
+			TokenFungible::total_supply(1)
+		);
+	})
+}
+
+#[test]
+fn spendable_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TokenFungible::spendable_balance(1, &ALICE), 0);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 100));
+
+		// nothing is held, all of the balance can be spent
+		assert_eq!(TokenFungible::spendable_balance(1, &ALICE), 100);
+		assert_eq!(
+			<TokenFungible as fungibles::Inspect<u64>>::reducible_balance(1, &ALICE, false),
+			100
+		);
+
+		// escrowed funds have left the balance
+		assert_ok!(TokenFungible::create_stream(Origin::signed(ALICE), 1, BOB, 40, 10, 20));
+		assert_eq!(TokenFungible::spendable_balance(1, &ALICE), 60);
+		assert_ok!(TokenFungible::transfer_all(Origin::signed(ALICE), 1, CHARLIE, false));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 60);
+		assert_eq!(TokenFungible::spendable_balance(1, &ALICE), 0);
+	})
+}
+
+#[test]
+fn fee_split_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1000));
+		// 3%
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 300));
+
+		assert_noop!(
+			TokenFungible::set_fee_split(Origin::signed(BOB), 1, vec![(4, 300)]),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenFungible::set_fee_split(Origin::signed(ALICE), 1, vec![(4, 100), (5, 100)]),
+			Error::<Test>::InvalidFeeSplit
+		);
+		assert_noop!(
+			TokenFungible::set_fee_split(Origin::signed(ALICE), 1, vec![(4, 300), (5, 0)]),
+			Error::<Test>::InvalidFeeSplit
+		);
+		assert_noop!(
+			TokenFungible::set_fee_split(
+				Origin::signed(ALICE),
+				1,
+				vec![(4, 60), (5, 60), (6, 60), (CHARLIE, 60), (0, 60)]
+			),
+			Error::<Test>::InvalidFeeSplit
+		);
+
+		// treasury, liquidity and a burn
+		assert_ok!(TokenFungible::set_fee_split(
+			Origin::signed(ALICE),
+			1,
+			vec![(4, 100), (5, 150), (0, 50)]
+		));
+
+		// the fee is 29 of 999; 9 and 14 are rounded down, and the burn takes the other 6
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 999));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 1);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 970);
+		assert_eq!(TokenFungible::balance_of(1, 4), 9);
+		assert_eq!(TokenFungible::balance_of(1, 5), 14);
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::total_supply(1), 994);
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, 4, 9, 2,
+		)));
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, 5, 14, 3,
+		)));
+		System::assert_has_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, 0, 6, 4,
+		)));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Transfer(
+			1, BOB, CHARLIE, 970, 5,
+		)));
+
+		// changing the fee sends it back to the owner
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 100));
+		assert!(TokenFungible::fee_split(1).is_empty());
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, BOB, 100));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 1);
+	})
+}
+
+#[test]
+fn sweep_pallet_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 1000));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 1000));
+		// 10%
+		assert_ok!(TokenFungible::set_reflection_fee(Origin::signed(ALICE), 1, 1_000));
+
+		// 90 sent to the pallet account by mistake, next to the 10 reflected
+		let pallet = TokenFungible::reflection_account_id();
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, pallet, 100));
+		assert_eq!(TokenFungible::balance_of(1, pallet), 100);
+		assert_eq!(TokenFungible::unclaimed_reflections(1), 10);
+		assert_eq!(TokenFungible::sweepable_balance(1), 90);
+
+		assert_ok!(TokenFungible::place_order(Origin::signed(CHARLIE), 1, 200, 2, 300));
+		let escrow = TokenFungible::order_escrow_account_id();
+		assert_eq!(TokenFungible::balance_of(1, escrow), 200);
+
+		assert_noop!(
+			TokenFungible::sweep_pallet_balance(Origin::signed(ALICE), 1, ALICE),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TokenFungible::sweep_pallet_balance(Origin::root(), 1, escrow),
+			Error::<Test>::PalletAccountRecipient
+		);
+		assert_noop!(
+			TokenFungible::sweep_pallet_balance(Origin::root(), 2, ALICE),
+			Error::<Test>::InvalidId
+		);
+
+		assert_ok!(TokenFungible::sweep_pallet_balance(Origin::root(), 1, ALICE));
+		System::assert_last_event(Event::TokenFungible(TokenFungibleEvent::Rescued(
+			1, pallet, ALICE, 90,
+		)));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 90);
+		assert_eq!(TokenFungible::balance_of(1, pallet), 10);
+		assert_eq!(TokenFungible::balance_of(1, escrow), 200);
+		assert_noop!(
+			TokenFungible::sweep_pallet_balance(Origin::root(), 1, ALICE),
+			Error::<Test>::NothingToWithdraw
+		);
+
+		// reflections stay claimable
+		assert_eq!(TokenFungible::claimable_reflections(1, &CHARLIE), 5);
+		assert_ok!(TokenFungible::claim_reflections(Origin::signed(CHARLIE), 1));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 805);
+		assert_eq!(TokenFungible::unclaimed_reflections(1), 5);
+		assert_eq!(TokenFungible::sweepable_balance(1), 0);
+	})
+}
+
+#[test]
+fn max_tx_amount_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+
+		assert_noop!(
+			TokenFungible::set_max_tx_amount(Origin::signed(BOB), 1, Some(100)),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_max_tx_amount(Origin::signed(ALICE), 1, Some(100)));
+		assert_eq!(Tokens::<Test>::get(1).unwrap().max_tx_amount, Some(100));
+
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 100));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 101),
+			Error::<Test>::ExceedsMaxTx
+		);
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 500));
+		assert_noop!(
+			TokenFungible::transfer_from(Origin::signed(CHARLIE), 1, ALICE, BOB, 101),
+			Error::<Test>::ExceedsMaxTx
+		);
+		// each leg of a batch is a transaction of its own, aggregated or not
+		for aggregate in [false, true] {
+			assert_noop!(
+				TokenFungible::transfer_batch(
+					Origin::signed(ALICE),
+					1,
+					vec![(BOB, 50), (CHARLIE, 101)],
+					aggregate
+				),
+				Error::<Test>::ExceedsMaxTx
+			);
+		}
+		assert_ok!(TokenFungible::transfer_batch(
+			Origin::signed(ALICE),
+			1,
+			vec![(BOB, 100), (CHARLIE, 100)],
+			true
+		));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 200);
+
+		// mints, burns and rescues aren't limited
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 500));
+		assert_ok!(TokenFungible::burn(Origin::signed(BOB), 1, 200));
+		assert_ok!(TokenFungible::rescue_tokens(Origin::root(), 1, BOB, CHARLIE, 500));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 600);
+
+		assert_ok!(TokenFungible::set_max_tx_amount(Origin::signed(ALICE), 1, None));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 700));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 700);
+	})
+}
+
+#[test]
+fn max_wallet_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1000));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, CHARLIE, 150));
+
+		assert_noop!(
+			TokenFungible::set_max_wallet(Origin::signed(BOB), 1, Some(100)),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_max_wallet(Origin::signed(ALICE), 1, Some(100)));
+		assert_eq!(Tokens::<Test>::get(1).unwrap().max_wallet, Some(100));
+
+		// the cap counts what the recipient already holds
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 60));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 41),
+			Error::<Test>::ExceedsMaxWallet
+		);
+		assert_noop!(
+			TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 41),
+			Error::<Test>::ExceedsMaxWallet
+		);
+		// batch legs are capped too, aggregated or not
+		for aggregate in [false, true] {
+			assert_noop!(
+				TokenFungible::transfer_batch(
+					Origin::signed(ALICE),
+					1,
+					vec![(4, 10), (BOB, 41)],
+					aggregate
+				),
+				Error::<Test>::ExceedsMaxWallet
+			);
+		}
+		assert_ok!(TokenFungible::transfer_batch(Origin::signed(ALICE), 1, vec![(BOB, 40)], true));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+
+		// a balance already over the cap is kept, can be spent and can't grow
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 1),
+			Error::<Test>::ExceedsMaxWallet
+		);
+		assert_ok!(TokenFungible::transfer(Origin::signed(CHARLIE), 1, 4, 100));
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 50);
+
+		// the owner and exempt accounts may hold any amount
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, ALICE, 100));
+		assert_noop!(
+			TokenFungible::set_max_wallet_exempt(Origin::signed(BOB), 1, 5, true),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenFungible::set_max_wallet_exempt(Origin::signed(ALICE), 1, 5, true));
+		assert!(TokenFungible::is_max_wallet_exempt(1, 5));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, 5, 500));
+		assert_eq!(TokenFungible::balance_of(1, 5), 500);
+
+		assert_ok!(TokenFungible::set_max_wallet_exempt(Origin::signed(ALICE), 1, 5, false));
+		assert_noop!(
+			TokenFungible::transfer(Origin::signed(ALICE), 1, 5, 1),
+			Error::<Test>::ExceedsMaxWallet
+		);
+
+		assert_ok!(TokenFungible::set_max_wallet(Origin::signed(ALICE), 1, None));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, BOB, 300));
+		assert_eq!(TokenFungible::balance_of(1, BOB), 300);
+	})
+}
+
+#[test]
+fn token_new_should_work() {
+	let token = Token::new(ALICE, b"W3G".to_vec(), b"W3G".to_vec(), 18, 7u64).unwrap();
+	assert_eq!(*token.owner(), ALICE);
+	assert_eq!(token.name(), &b"W3G".to_vec());
+	assert_eq!(token.symbol(), &b"W3G".to_vec());
+	assert_eq!(token.decimals(), 18);
+	assert_eq!(token.total_supply(), 0);
+	assert_eq!(token.max_supply(), None);
+	assert_eq!(token.created_at, 7);
+	assert_eq!(token.last_activity, 7);
+	assert_eq!(token.max_allowance, None);
+	assert_eq!(token.transfer_policy, TransferPolicy::Open);
+	assert_eq!(token.max_holders, None);
+	assert_eq!(token.transfer_cooldown, None);
+	assert!(!token.require_memo);
+	assert!(!token.whole_units_only);
+	assert!(token.emit_transfer_events);
+	assert_eq!(token.max_tx_amount, None);
+	assert_eq!(token.max_wallet, None);
+
+	assert!(Token::new(ALICE, (), (), MAX_DECIMALS, 0u64).is_some());
+	assert!(Token::new(ALICE, (), (), MAX_DECIMALS + 1, 0u64).is_none());
+
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TokenFungible::create_token(
+				Origin::signed(ALICE),
+				1,
+				b"W3G".to_vec(),
+				b"W3G".to_vec(),
+				MAX_DECIMALS + 1
+			),
+			Error::<Test>::InvalidDecimals
+		);
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			MAX_DECIMALS
+		));
+		assert_eq!(Tokens::<Test>::get(1).unwrap().decimals(), MAX_DECIMALS);
+	})
+}
+
+#[test]
+fn fee_rounding_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			18
+		));
+		assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, BOB, 10_000));
+		// 1%, so 2.6 of 260 and 2.4 of 240
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 100));
+
+		let cases = [
+			(RoundingMode::Floor, 260, 2),
+			(RoundingMode::Floor, 240, 2),
+			(RoundingMode::Ceil, 260, 3),
+			(RoundingMode::Ceil, 240, 3),
+			(RoundingMode::Nearest, 260, 3),
+			(RoundingMode::Nearest, 240, 2),
+		];
+		for (mode, amount, fee) in cases {
+			FeeRounding::set(&mode);
+			assert_eq!(TokenFungible::transfer_fee(1, &BOB, amount), fee);
+
+			let owner_before = TokenFungible::balance_of(1, ALICE);
+			let charlie_before = TokenFungible::balance_of(1, CHARLIE);
+			assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, amount));
+			assert_eq!(TokenFungible::balance_of(1, ALICE), owner_before + fee);
+			assert_eq!(TokenFungible::balance_of(1, CHARLIE), charlie_before + amount - fee);
+		}
+
+		// split shares round the same way, and the last share keeps the total exact
+		assert_ok!(TokenFungible::set_transfer_fee(Origin::signed(ALICE), 1, 300));
+		assert_ok!(TokenFungible::set_fee_split(
+			Origin::signed(ALICE),
+			1,
+			vec![(4, 150), (5, 150)]
+		));
+		// a fee of 7.5 on 250, split 3.75 and 3.75
+		FeeRounding::set(&RoundingMode::Ceil);
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 250));
+		assert_eq!(TokenFungible::balance_of(1, 4), 4);
+		assert_eq!(TokenFungible::balance_of(1, 5), 4);
+		FeeRounding::set(&RoundingMode::Floor);
+		assert_ok!(TokenFungible::transfer(Origin::signed(BOB), 1, CHARLIE, 250));
+		assert_eq!(TokenFungible::balance_of(1, 4), 7);
+		assert_eq!(TokenFungible::balance_of(1, 5), 8);
+	})
+}
+
+#[test]
+fn create_token_idempotent_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenFungible::create_token_idempotent(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"Web3Games".to_vec(),
+			18
+		));
+		let id = TokenFungible::idempotent_token_id(
+			ALICE,
+			BoundedVec::<u8, StringLimit>::try_from(b"W3G".to_vec()).unwrap(),
+		)
+		.unwrap();
+		assert_eq!(TokenFungible::token_symbol(id), b"W3G".to_vec());
+		assert_eq!(TokenFungible::token_count(), 1);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&ALICE), CreateTokenDeposit::get());
+
+		// a retry returns the first token and reserves nothing more
+		assert_ok!(TokenFungible::create_token_idempotent(
+			Origin::signed(ALICE),
+			b"W3G".to_vec(),
+			b"Web3Games".to_vec(),
+			18
+		));
+		assert_eq!(
+			TokenFungible::do_create_token_idempotent(&ALICE, b"W3G".to_vec(), b"W3G".to_vec(), 6),
+			Ok(id)
+		);
+		assert_eq!(TokenFungible::token_count(), 1);
+		assert_eq!(TokenFungible::token_decimals(id), 18);
+		assert_eq!(<Test as Config>::Currency::reserved_balance(&ALICE), CreateTokenDeposit::get());
+
+		// another symbol or another creator is another token
+		let other_symbol = TokenFungible::do_create_token_idempotent(
+			&ALICE,
+			b"USDT".to_vec(),
+			b"USDT".to_vec(),
+			6,
+		)
+		.unwrap();
+		let other_creator =
+			TokenFungible::do_create_token_idempotent(&BOB, b"W3G".to_vec(), b"W3G".to_vec(), 18)
+				.unwrap();
+		assert_ne!(other_symbol, id);
+		assert_ne!(other_creator, id);
+		assert_ne!(other_creator, other_symbol);
+		assert_eq!(TokenFungible::token_count(), 3);
+		assert_eq!(
+			<Test as Config>::Currency::reserved_balance(&ALICE),
+			2 * CreateTokenDeposit::get()
+		);
+	})
+}
@@ -46,20 +46,25 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_token_fungible.
 pub trait WeightInfo {
-	fn create_token() -> Weight;
+	fn create_token(n: u32, s: u32) -> Weight;
 	fn mint() -> Weight;
 	fn approve() -> Weight;
 	fn burn() -> Weight;
 	fn transfer() -> Weight;
 	fn transfer_from() -> Weight;
+	fn set_config() -> Weight;
 }
 
 /// Weights for pallet_token_fungible using the Web3Games node and recommended hardware.
 pub struct W3GWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for W3GWeight<T> {
 	// Storage: TokenFungible Tokens (r:1 w:1)
-	fn create_token() -> Weight {
+	fn create_token(n: u32, s: u32) -> Weight {
 		(15_000_000 as Weight)
+			// The per-byte slopes are hand estimates, not benchmark output. Rerun the
+			// `create_token` benchmark to replace them.
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
@@ -97,12 +102,23 @@ impl<T: frame_system::Config> WeightInfo for W3GWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
+	// Storage: TokenFungible Tokens (r:1 w:1)
+	// Storage: TokenFungible LastOwnerAction (r:0 w:1)
+	fn set_config() -> Weight {
+		// Hand estimate, not benchmark output. Rerun the `set_config` benchmark to replace it.
+		(16_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn create_token() -> Weight {
+	fn create_token(n: u32, s: u32) -> Weight {
 		(15_000_000 as Weight)
+			// Hand estimated, see `W3GWeight::create_token`.
+			.saturating_add((2_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((2_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
@@ -131,4 +147,9 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
+	fn set_config() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
 }
@@ -15,6 +15,25 @@ benchmarks! {
 		let alice: T::AccountId = account("alice", 0, SEED);
 	}: _(RawOrigin::Signed(alice), 1u32.into(), vec![0u8; 10], vec![0u8; 10], vec![0u8; 20])
 
+	set_base_uri {
+		let alice: T::AccountId = account("alice", 0, SEED);
+
+		let _ = TokenNonFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), vec![0u8; 10], vec![0u8; 10], vec![0u8; 20]);
+	}: _(RawOrigin::Signed(alice), 1u32.into(), vec![1u8; 20])
+
+	freeze_metadata {
+		let alice: T::AccountId = account("alice", 0, SEED);
+
+		let _ = TokenNonFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), vec![0u8; 10], vec![0u8; 10], vec![0u8; 20]);
+	}: _(RawOrigin::Signed(alice), 1u32.into())
+
+	set_royalty {
+		let alice: T::AccountId = account("alice", 0, SEED);
+		let bob: T::AccountId = account("bob", 0, SEED);
+
+		let _ = TokenNonFungible::<T>::create_token(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(alice.clone())), 1u32.into(), vec![0u8; 10], vec![0u8; 10], vec![0u8; 20]);
+	}: _(RawOrigin::Signed(alice), 1u32.into(), bob, 500)
+
 	mint {
 		let alice: T::AccountId = account("alice", 0, SEED);
 		// let bob: T::AccountId = account("bob", 0, SEED);
@@ -26,7 +26,7 @@ use frame_support::{
 	BoundedVec, PalletId,
 };
 use pallet_support::{NonFungibleEnumerable, NonFungibleMetadata};
-use primitives::TokenIndex;
+use primitives::{Balance, TokenIndex};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{AtLeast32BitUnsigned, One, TrailingZeroInput},
@@ -51,6 +51,9 @@ mod tests;
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// Basis points making up the whole of a sale price.
+pub const MAX_ROYALTY_BPS: u16 = 10_000;
+
 #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub struct Token<AccountId, BoundedString> {
 	owner: AccountId,
@@ -59,6 +62,30 @@ pub struct Token<AccountId, BoundedString> {
 	base_uri: BoundedString,
 }
 
+impl<AccountId, BoundedString> Token<AccountId, BoundedString> {
+	pub fn owner(&self) -> &AccountId {
+		&self.owner
+	}
+
+	pub fn name(&self) -> &BoundedString {
+		&self.name
+	}
+
+	pub fn symbol(&self) -> &BoundedString {
+		&self.symbol
+	}
+
+	pub fn base_uri(&self) -> &BoundedString {
+		&self.base_uri
+	}
+}
+
+/// A collection as stored.
+pub type CollectionOf<T> = Token<
+	<T as frame_system::Config>::AccountId,
+	BoundedVec<u8, <T as pallet::Config>::StringLimit>,
+>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -164,6 +191,8 @@ pub mod pallet {
 	pub(super) type TotalSupply<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::NonFungibleTokenId, u32, ValueQuery>;
 
+	/// Every token of a collection at indices `0..TotalSupply`, with no gaps. Burning moves the
+	/// last token into the freed index, so indices of other tokens may change on burns.
 	#[pallet::storage]
 	pub(super) type AllTokens<T: Config> = StorageDoubleMap<
 		_,
@@ -186,6 +215,7 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The tokens of an owner at indices `0..balance_of`, kept contiguous the same way.
 	#[pallet::storage]
 	pub(super) type OwnedTokens<T: Config> = StorageDoubleMap<
 		_,
@@ -208,6 +238,29 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// Collections whose base uri can no longer be changed.
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_frozen)]
+	pub(super) type MetadataFrozen<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::NonFungibleTokenId, bool, ValueQuery>;
+
+	/// Uris set for single tokens, used instead of the collection's base uri.
+	#[pallet::storage]
+	pub(super) type TokenUris<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::NonFungibleTokenId,
+		Blake2_128Concat,
+		T::TokenId,
+		BoundedVec<u8, T::StringLimit>,
+	>;
+
+	/// Recipient and basis points of the royalty owed on sales of any item of a collection.
+	#[pallet::storage]
+	#[pallet::getter(fn royalty)]
+	pub(super) type Royalties<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::NonFungibleTokenId, (T::AccountId, u16)>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -215,6 +268,9 @@ pub mod pallet {
 		Transfer(T::NonFungibleTokenId, T::AccountId, T::AccountId, T::TokenId),
 		Approval(T::NonFungibleTokenId, T::AccountId, T::AccountId, T::TokenId),
 		ApprovalForAll(T::NonFungibleTokenId, T::AccountId, T::AccountId, bool),
+		BaseUriUpdated(T::NonFungibleTokenId, Vec<u8>),
+		MetadataFrozen(T::NonFungibleTokenId),
+		RoyaltySet(T::NonFungibleTokenId, T::AccountId, u16),
 	}
 
 	#[pallet::error]
@@ -234,6 +290,9 @@ pub mod pallet {
 		ConfuseBehavior,
 		TransferTokenNotOwn,
 		NotFound,
+		MetadataFrozen,
+		TransferToZeroAddress,
+		InvalidRoyalty,
 	}
 
 	#[pallet::hooks]
@@ -253,6 +312,59 @@ pub mod pallet {
 			Self::do_create_token(&who, id, name, symbol, base_uri)
 		}
 
+		/// Replace the base uri token uris are built from. Only the collection owner may do
+		/// this.
+		#[pallet::weight(T::WeightInfo::set_base_uri())]
+		pub fn set_base_uri(
+			origin: OriginFor<T>,
+			id: T::NonFungibleTokenId,
+			base_uri: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_set_base_uri(&who, id, base_uri)
+		}
+
+		/// Permanently lock the base uri of a collection. Only the collection owner may do
+		/// this.
+		#[pallet::weight(T::WeightInfo::freeze_metadata())]
+		pub fn freeze_metadata(origin: OriginFor<T>, id: T::NonFungibleTokenId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			ensure!(Self::has_permission(id, &who), Error::<T>::NoPermission);
+
+			MetadataFrozen::<T>::insert(id, true);
+
+			Self::deposit_event(Event::MetadataFrozen(id));
+
+			Ok(())
+		}
+
+		/// Set the royalty paid to `recipient` on sales of the collection's items, in basis
+		/// points of the sale price. Zero basis points remove the royalty. Only the collection
+		/// owner may do this.
+		#[pallet::weight(T::WeightInfo::set_royalty())]
+		pub fn set_royalty(
+			origin: OriginFor<T>,
+			id: T::NonFungibleTokenId,
+			recipient: T::AccountId,
+			royalty_bps: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::exists(id), Error::<T>::InvalidId);
+			ensure!(Self::has_permission(id, &who), Error::<T>::NoPermission);
+			ensure!(royalty_bps <= MAX_ROYALTY_BPS, Error::<T>::InvalidRoyalty);
+
+			if royalty_bps == 0 {
+				Royalties::<T>::remove(id);
+			} else {
+				Royalties::<T>::insert(id, (recipient.clone(), royalty_bps));
+			}
+
+			Self::deposit_event(Event::RoyaltySet(id, recipient, royalty_bps));
+
+			Ok(())
+		}
+
 		#[pallet::weight(T::WeightInfo::approve())]
 		pub fn approve(
 			origin: OriginFor<T>,
@@ -298,6 +410,23 @@ pub mod pallet {
 			Self::do_mint(&who, id, &to, token_id)
 		}
 
+		/// Mint a token whose uri is `uri` rather than derived from the base uri.
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub fn mint_with_uri(
+			origin: OriginFor<T>,
+			id: T::NonFungibleTokenId,
+			to: T::AccountId,
+			token_id: T::TokenId,
+			uri: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bounded_uri: BoundedVec<u8, T::StringLimit> =
+				uri.try_into().map_err(|_| Error::<T>::BadMetadata)?;
+			Self::do_mint(&who, id, &to, token_id)?;
+			TokenUris::<T>::insert(id, token_id, bounded_uri);
+			Ok(())
+		}
+
 		#[pallet::weight(T::WeightInfo::burn())]
 		pub fn burn(
 			origin: OriginFor<T>,
@@ -319,10 +448,57 @@ impl<T: Config> Pallet<T> {
 		Tokens::<T>::contains_key(id)
 	}
 
+	/// The EIP-2981 royalty owed on a sale for `sale_price`, rounded down, and who it is
+	/// owed to. `None` if the collection has no royalty.
+	pub fn royalty_info(
+		id: T::NonFungibleTokenId,
+		sale_price: Balance,
+	) -> Option<(T::AccountId, Balance)> {
+		let (recipient, royalty_bps) = Royalties::<T>::get(id)?;
+		let bps = Balance::from(royalty_bps);
+		let max = Balance::from(MAX_ROYALTY_BPS);
+		// Split the price so the multiplication can't overflow.
+		let amount = sale_price / max * bps + sale_price % max * bps / max;
+		Some((recipient, amount))
+	}
+
+	/// Read a collection in one go, for callers needing several of its fields.
+	pub fn collection(id: T::NonFungibleTokenId) -> Option<CollectionOf<T>> {
+		Tokens::<T>::get(id)
+	}
+
+	/// The uri of `token_id` in a collection already read with `collection`.
+	pub fn token_uri_in(
+		collection: &CollectionOf<T>,
+		id: T::NonFungibleTokenId,
+		token_id: T::TokenId,
+	) -> Vec<u8>
+	where
+		T::TokenId: Into<u128>,
+	{
+		if let Some(uri) = TokenUris::<T>::get(id, token_id) {
+			return uri.into_inner()
+		}
+		let token_id: u128 = token_id.into();
+		let token_id_buf: Vec<u8> = token_id.to_string().as_bytes().to_vec();
+		collection.base_uri.iter().copied().chain(token_id_buf).collect::<Vec<_>>()
+	}
+
 	pub fn token_exists(id: T::NonFungibleTokenId, token_id: T::TokenId) -> bool {
 		Owners::<T>::contains_key(id, token_id)
 	}
 
+	/// Return up to `limit` tokens held by `owner`, starting at owner index `start`.
+	pub fn tokens_of_owner(
+		id: T::NonFungibleTokenId,
+		owner: &T::AccountId,
+		start: TokenIndex,
+		limit: TokenIndex,
+	) -> Vec<T::TokenId> {
+		let end = Self::balance_of(id, owner).min(start.saturating_add(limit));
+		(start..end).map(|index| OwnedTokens::<T>::get(id, (owner, index))).collect()
+	}
+
 	pub fn do_create_token(
 		who: &T::AccountId,
 		id: T::NonFungibleTokenId,
@@ -353,6 +529,29 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	pub fn do_set_base_uri(
+		who: &T::AccountId,
+		id: T::NonFungibleTokenId,
+		base_uri: Vec<u8>,
+	) -> DispatchResult {
+		ensure!(Self::exists(id), Error::<T>::InvalidId);
+		ensure!(Self::has_permission(id, who), Error::<T>::NoPermission);
+		ensure!(!MetadataFrozen::<T>::get(id), Error::<T>::MetadataFrozen);
+
+		let bounded_base_uri: BoundedVec<u8, T::StringLimit> =
+			base_uri.clone().try_into().map_err(|_| Error::<T>::BadMetadata)?;
+
+		Tokens::<T>::mutate(id, |maybe_token| {
+			if let Some(token) = maybe_token {
+				token.base_uri = bounded_base_uri;
+			}
+		});
+
+		Self::deposit_event(Event::BaseUriUpdated(id, base_uri));
+
+		Ok(())
+	}
+
 	pub fn do_approve(
 		who: &T::AccountId,
 		id: T::NonFungibleTokenId,
@@ -415,6 +614,8 @@ impl<T: Config> Pallet<T> {
 			Owners::<T>::get(id, token_id) == Some(from.clone()),
 			Error::<T>::TransferTokenNotOwn
 		);
+		// Burning goes through `burn`, tokens held by the zero account would corrupt enumeration.
+		ensure!(to != &Self::zero_account_id(), Error::<T>::TransferToZeroAddress);
 
 		if from == to {
 			return Ok(())
@@ -436,7 +637,7 @@ impl<T: Config> Pallet<T> {
 		Self::remove_token_from_owner_enumeration(id, from, token_id)?;
 		Self::add_token_to_owner_enumeration(id, to, token_id)?;
 
-		Self::clear_approval(id, token_id)?;
+		Self::clear_approval(id, from, token_id)?;
 
 		Balances::<T>::insert(id, from, new_balance_from);
 		Balances::<T>::insert(id, to, new_balance_to);
@@ -467,6 +668,9 @@ impl<T: Config> Pallet<T> {
 		Self::add_token_to_all_tokens_enumeration(id, token_id)?;
 		Self::add_token_to_owner_enumeration(id, to, token_id)?;
 
+		// A fresh token never inherits an approval.
+		TokenApprovals::<T>::remove(id, token_id);
+
 		Balances::<T>::insert(id, to, new_balance);
 		Owners::<T>::insert(id, token_id, to);
 
@@ -498,10 +702,11 @@ impl<T: Config> Pallet<T> {
 		Self::remove_token_from_all_tokens_enumeration(id, token_id)?;
 		Self::remove_token_from_owner_enumeration(id, &owner, token_id)?;
 
-		Self::clear_approval(id, token_id)?;
+		Self::clear_approval(id, &owner, token_id)?;
 
 		Balances::<T>::insert(id, &owner, new_balance);
 		Owners::<T>::remove(id, token_id);
+		TokenUris::<T>::remove(id, token_id);
 
 		Self::deposit_event(Event::Transfer(id.clone(), owner, Self::zero_account_id(), token_id));
 
@@ -525,8 +730,17 @@ impl<T: Config> Pallet<T> {
 		*who == token.owner
 	}
 
-	fn clear_approval(id: T::NonFungibleTokenId, token_id: T::TokenId) -> DispatchResult {
+	/// Drop the approval of a token leaving `owner`, signalling it with an `Approval` to the
+	/// zero account like ERC721 does.
+	fn clear_approval(
+		id: T::NonFungibleTokenId,
+		owner: &T::AccountId,
+		token_id: T::TokenId,
+	) -> DispatchResult {
 		TokenApprovals::<T>::remove(id, token_id);
+
+		Self::deposit_event(Event::Approval(id, owner.clone(), Self::zero_account_id(), token_id));
+
 		Ok(())
 	}
 
@@ -631,10 +845,7 @@ where
 	}
 
 	fn token_uri(id: Self::NonFungibleTokenId, token_id: Self::TokenId) -> Vec<u8> {
-		let base_uri_buf: Vec<u8> = Tokens::<T>::get(id).unwrap().base_uri.to_vec();
-		let token_id: u128 = token_id.into();
-		let token_id_buf: Vec<u8> = token_id.to_string().as_bytes().to_vec();
-		base_uri_buf.into_iter().chain(token_id_buf).collect::<Vec<_>>()
+		Self::token_uri_in(&Tokens::<T>::get(id).unwrap(), id, token_id)
 	}
 }
 
@@ -283,3 +283,272 @@ fn transfer_from_should_not_work() {
 		assert_eq!(TokenNonFungible::total_supply(1), 2);
 	})
 }
+
+#[test]
+fn tokens_of_owner_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 0, 10), vec![]);
+
+		for token_id in 10..15 {
+			assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, BOB, token_id));
+		}
+
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 0, 2), vec![10, 11]);
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 2, 2), vec![12, 13]);
+		// partial last page
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 4, 2), vec![14]);
+		// beyond the end
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 5, 2), vec![]);
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, TokenIndex::MAX, 2), vec![]);
+
+		assert_eq!(TokenNonFungible::tokens_of_owner(1, &BOB, 0, 10), vec![10, 11, 12, 13, 14]);
+	})
+}
+
+#[test]
+fn approval_should_be_consumed_by_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 0));
+
+		// only the owner (or an operator) can approve
+		assert_noop!(
+			TokenNonFungible::approve(Origin::signed(BOB), 1, BOB, 0),
+			Error::<Test>::NotOwnerOrApproved
+		);
+
+		assert_ok!(TokenNonFungible::approve(Origin::signed(ALICE), 1, BOB, 0));
+		assert_eq!(TokenNonFungible::get_approved(1, 0), Some(BOB));
+
+		assert_ok!(TokenNonFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 0));
+		assert_eq!(TokenNonFungible::owner_of(1, 0), Some(CHARLIE));
+		assert_eq!(TokenNonFungible::get_approved(1, 0), None);
+
+		// the approval doesn't survive the transfer
+		assert_noop!(
+			TokenNonFungible::transfer_from(Origin::signed(BOB), 1, CHARLIE, ALICE, 0),
+			Error::<Test>::NotOwnerOrApproved
+		);
+	})
+}
+
+#[test]
+fn freeze_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 0));
+
+		assert_noop!(
+			TokenNonFungible::set_base_uri(Origin::signed(BOB), 1, b"ipfs://".to_vec()),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenNonFungible::set_base_uri(Origin::signed(ALICE), 1, b"ipfs://".to_vec()));
+		assert_eq!(TokenNonFungible::token_uri(1, 0), b"ipfs://0");
+
+		assert_noop!(
+			TokenNonFungible::freeze_metadata(Origin::signed(BOB), 1),
+			Error::<Test>::NoPermission
+		);
+		assert_ok!(TokenNonFungible::freeze_metadata(Origin::signed(ALICE), 1));
+		assert!(TokenNonFungible::metadata_frozen(1));
+
+		assert_noop!(
+			TokenNonFungible::set_base_uri(Origin::signed(ALICE), 1, b"https://".to_vec()),
+			Error::<Test>::MetadataFrozen
+		);
+		assert_eq!(TokenNonFungible::token_uri(1, 0), b"ipfs://0");
+	})
+}
+
+#[test]
+fn transfer_should_signal_cleared_approval() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 0));
+		assert_ok!(TokenNonFungible::approve(Origin::signed(ALICE), 1, BOB, 0));
+
+		assert_ok!(TokenNonFungible::transfer_from(Origin::signed(BOB), 1, ALICE, CHARLIE, 0));
+		System::assert_has_event(crate::mock::Event::TokenNonFungible(
+			TokenFungibleEvent::Approval(1, ALICE, 0, 0),
+		));
+		System::assert_last_event(crate::mock::Event::TokenNonFungible(
+			TokenFungibleEvent::Transfer(1, ALICE, CHARLIE, 0),
+		));
+
+		// the stale approval can't move the token on
+		assert_noop!(
+			TokenNonFungible::transfer_from(Origin::signed(BOB), 1, CHARLIE, BOB, 0),
+			Error::<Test>::NotOwnerOrApproved
+		);
+
+		// a burnt and re-minted token starts without an approval
+		assert_ok!(TokenNonFungible::approve(Origin::signed(CHARLIE), 1, BOB, 0));
+		assert_ok!(TokenNonFungible::burn(Origin::signed(CHARLIE), 1, 0));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 0));
+		assert_eq!(TokenNonFungible::get_approved(1, 0), None);
+	})
+}
+
+#[test]
+fn transfer_to_zero_address_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 0));
+
+		assert_noop!(
+			TokenNonFungible::transfer_from(Origin::signed(ALICE), 1, ALICE, 0, 0),
+			Error::<Test>::TransferToZeroAddress
+		);
+		assert_eq!(TokenNonFungible::owner_of(1, 0), Some(ALICE));
+		assert_eq!(TokenNonFungible::balance_of(1, 0), 0);
+		assert_eq!(TokenNonFungible::total_supply(1), 1);
+	})
+}
+
+#[test]
+fn royalty_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_eq!(TokenNonFungible::royalty_info(1, 10_000), None);
+
+		assert_noop!(
+			TokenNonFungible::set_royalty(Origin::signed(BOB), 1, BOB, 250),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenNonFungible::set_royalty(Origin::signed(ALICE), 1, BOB, MAX_ROYALTY_BPS + 1),
+			Error::<Test>::InvalidRoyalty
+		);
+		assert_noop!(
+			TokenNonFungible::set_royalty(Origin::signed(ALICE), 2, BOB, 250),
+			Error::<Test>::InvalidId
+		);
+
+		// 2.5%
+		assert_ok!(TokenNonFungible::set_royalty(Origin::signed(ALICE), 1, BOB, 250));
+		assert_eq!(TokenNonFungible::royalty(1), Some((BOB, 250)));
+		assert_eq!(TokenNonFungible::royalty_info(1, 10_000), Some((BOB, 250)));
+		assert_eq!(TokenNonFungible::royalty_info(1, 1_000), Some((BOB, 25)));
+		// rounded down
+		assert_eq!(TokenNonFungible::royalty_info(1, 39), Some((BOB, 0)));
+		assert_eq!(TokenNonFungible::royalty_info(1, 12_345), Some((BOB, 308)));
+		assert_eq!(TokenNonFungible::royalty_info(1, u128::MAX), Some((BOB, u128::MAX / 40)));
+
+		assert_ok!(TokenNonFungible::set_royalty(Origin::signed(ALICE), 1, CHARLIE, 0));
+		assert_eq!(TokenNonFungible::royalty(1), None);
+		assert_eq!(TokenNonFungible::royalty_info(1, 10_000), None);
+	})
+}
+
+#[test]
+fn mint_with_uri_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		assert_noop!(
+			TokenNonFungible::mint_with_uri(
+				Origin::signed(BOB),
+				1,
+				BOB,
+				0,
+				b"ipfs://token-0".to_vec()
+			),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			TokenNonFungible::mint_with_uri(Origin::signed(ALICE), 1, BOB, 0, [0u8; 51].to_vec()),
+			Error::<Test>::BadMetadata
+		);
+
+		assert_ok!(TokenNonFungible::mint_with_uri(
+			Origin::signed(ALICE),
+			1,
+			BOB,
+			0,
+			b"ipfs://token-0".to_vec()
+		));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, BOB, 1));
+		assert_eq!(TokenNonFungible::owner_of(1, 0), Some(BOB));
+		assert_eq!(TokenNonFungible::token_uri(1, 0), b"ipfs://token-0".to_vec());
+		assert_eq!(TokenNonFungible::token_uri(1, 1), b"https://web3games.com/1".to_vec());
+
+		// a burnt token takes its uri with it
+		assert_ok!(TokenNonFungible::burn(Origin::signed(BOB), 1, 0));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, BOB, 0));
+		assert_eq!(TokenNonFungible::token_uri(1, 0), b"https://web3games.com/0".to_vec());
+	})
+}
+
+#[test]
+fn enumeration_should_stay_contiguous_after_burn() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TokenNonFungible::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"W3G".to_vec(),
+			b"W3G".to_vec(),
+			b"https://web3games.com/".to_vec(),
+		));
+		for token_id in 1..=5 {
+			assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, token_id));
+		}
+		assert_ok!(TokenNonFungible::burn(Origin::signed(ALICE), 1, 3));
+
+		assert_eq!(TokenNonFungible::total_supply(1), 4);
+		let mut all: Vec<_> =
+			(0..4).map(|index| TokenNonFungible::token_by_index(1, index)).collect();
+		// the last token took the freed index
+		assert_eq!(all, vec![1, 2, 5, 4]);
+		all.sort();
+		assert_eq!(all, vec![1, 2, 4, 5]);
+
+		assert_eq!(TokenNonFungible::balance_of(1, ALICE), 4);
+		let mut owned: Vec<_> = (0..4)
+			.map(|index| TokenNonFungible::token_of_owner_by_index(1, ALICE, index))
+			.collect();
+		owned.sort();
+		assert_eq!(owned, vec![1, 2, 4, 5]);
+	})
+}
@@ -47,6 +47,9 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_token_non_fungible.
 pub trait WeightInfo {
 	fn create_token() -> Weight;
+	fn set_base_uri() -> Weight;
+	fn freeze_metadata() -> Weight;
+	fn set_royalty() -> Weight;
 	fn mint() -> Weight;
 	fn burn() -> Weight;
 	fn approve() -> Weight;
@@ -63,6 +66,27 @@ impl<T: frame_system::Config> WeightInfo for W3GWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	// Storage: TokenNonFungible Tokens (r:1 w:1)
+	// Storage: TokenNonFungible MetadataFrozen (r:1 w:0)
+	fn set_base_uri() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TokenNonFungible Tokens (r:1 w:0)
+	// Storage: TokenNonFungible MetadataFrozen (r:0 w:1)
+	fn freeze_metadata() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TokenNonFungible Tokens (r:1 w:0)
+	// Storage: TokenNonFungible Royalties (r:0 w:1)
+	fn set_royalty() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 	// Storage: TokenNonFungible Tokens (r:1 w:0)
 	// Storage: TokenNonFungible Owners (r:1 w:1)
 	// Storage: TokenNonFungible Balances (r:1 w:1)
@@ -120,6 +144,21 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+	fn set_base_uri() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn freeze_metadata() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_royalty() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 	fn mint() -> Weight {
 		(34_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
@@ -27,7 +27,7 @@ use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 use pallet_support::AccountMapping;
-use sp_core::H160;
+use sp_core::{H160, U256};
 use sp_std::{marker::PhantomData, prelude::*};
 
 mod exchange;
@@ -38,6 +38,12 @@ mod token_fungible;
 mod token_multi;
 mod token_non_fungible;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub use exchange::ExchangeExtension;
 pub use farming::FarmingExtension;
 pub use launchpad::LaunchpadExtension;
@@ -93,7 +99,8 @@ where
 		+ pallet_exchange::Config
 		+ pallet_marketplace::Config
 		+ pallet_farming::Config
-		+ pallet_launchpad::Config,
+		+ pallet_launchpad::Config
+		+ pallet_timestamp::Config,
 	R::Call: From<pallet_token_fungible::Call<R>>,
 	R::Call: From<pallet_token_non_fungible::Call<R>>,
 	R::Call: From<pallet_token_multi::Call<R>>,
@@ -102,6 +109,7 @@ where
 	R::Call: From<pallet_farming::Call<R>>,
 	R::Call: From<pallet_launchpad::Call<R>>,
 	<R as pallet_token_fungible::Config>::FungibleTokenId: From<u128> + Into<u128>,
+	<R as pallet_timestamp::Config>::Moment: Into<U256>,
 	<R as pallet_exchange::Config>::PoolId: From<u128> + Into<u128>,
 	<R as pallet_token_non_fungible::Config>::NonFungibleTokenId: From<u128> + Into<u128>,
 	<R as pallet_token_non_fungible::Config>::TokenId: From<u128> + Into<u128>,
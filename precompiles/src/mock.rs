@@ -0,0 +1,288 @@
+// This file is part of Web3Games.
+
+// Copyright (C) 2021-2022 Web3Games https://web3games.org
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	FungibleTokenExtension, MultiTokenExtension, NonFungibleTokenExtension,
+	FT_PRECOMPILE_ADDRESS_PREFIX, MT_PRECOMPILE_ADDRESS_PREFIX, NFT_PRECOMPILE_ADDRESS_PREFIX,
+};
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{ConstBool, ConstU16, ConstU32, ConstU64, ConstU8, Everything, Nothing, Randomness},
+	weights::RuntimeDbWeight,
+	PalletId,
+};
+use pallet_evm::{
+	AddressMapping, EnsureAddressNever, EnsureAddressRoot, PrecompileHandle, PrecompileResult,
+	PrecompileSet, SubstrateBlockHashMapping,
+};
+use pallet_support::{AccountMapping, TokenIdConversion};
+use primitives::Balance;
+use sp_core::{H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const MILLICENTS: Balance = 10_000_000_000_000;
+pub const CENTS: Balance = 1_000 * MILLICENTS; // assume this is worth about a cent.
+pub const DOLLARS: Balance = 100 * CENTS;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		TokenFungible: pallet_token_fungible::{Pallet, Call, Storage, Event<T>},
+		TokenNonFungible: pallet_token_non_fungible::{Pallet, Call, Storage, Event<T>},
+		TokenMulti: pallet_token_multi::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	// Reads are priced apart from writes, so a call's cost tells how many reads it paid for.
+	pub const MockDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1_000, write: 10_000 };
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = MockDbWeight;
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+/// Maps an address to the account in its low 8 bytes, the inverse of `AccountMapping`, so
+/// `evm_address(n)` is account `n`.
+pub struct MockAddressMapping;
+
+impl AddressMapping<u64> for MockAddressMapping {
+	fn into_account_id(address: H160) -> u64 {
+		let mut data = [0u8; 8];
+		data.copy_from_slice(&address.to_fixed_bytes()[12..20]);
+		u64::from_be_bytes(data)
+	}
+}
+
+impl AccountMapping<u64> for Test {
+	fn into_evm_address(account: u64) -> H160 {
+		evm_address(account)
+	}
+}
+
+/// The address of `account` in the EVM.
+pub fn evm_address(account: u64) -> H160 {
+	H160::from_low_u64_be(account)
+}
+
+/// The token precompiles of `Web3GamesPrecompiles`, without the other pallets it needs.
+pub struct Precompiles;
+
+impl PrecompileSet for Precompiles {
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		match handle.code_address() {
+			a if &a.to_fixed_bytes()[0..4] == FT_PRECOMPILE_ADDRESS_PREFIX =>
+				FungibleTokenExtension::<Test>::new().execute(handle),
+			a if &a.to_fixed_bytes()[0..4] == NFT_PRECOMPILE_ADDRESS_PREFIX =>
+				NonFungibleTokenExtension::<Test>::new().execute(handle),
+			a if &a.to_fixed_bytes()[0..4] == MT_PRECOMPILE_ADDRESS_PREFIX =>
+				MultiTokenExtension::<Test>::new().execute(handle),
+			_ => None,
+		}
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		FungibleTokenExtension::<Test>::new().is_precompile(address) ||
+			NonFungibleTokenExtension::<Test>::new().is_precompile(address) ||
+			MultiTokenExtension::<Test>::new().is_precompile(address)
+	}
+}
+
+/// The precompile address of fungible token `id`.
+pub fn fungible_token_address(id: u128) -> H160 {
+	FungibleTokenExtension::<Test>::into_address(id)
+}
+
+/// The precompile address of collection `id`.
+pub fn non_fungible_token_address(id: u128) -> H160 {
+	NonFungibleTokenExtension::<Test>::into_address(id)
+}
+
+/// The precompile address of multi token `id`.
+pub fn multi_token_address(id: u128) -> H160 {
+	MultiTokenExtension::<Test>::into_address(id)
+}
+
+parameter_types! {
+	pub PrecompilesValue: Precompiles = Precompiles;
+}
+
+impl pallet_evm::Config for Test {
+	type FeeCalculator = ();
+	type GasWeightMapping = ();
+	type BlockHashMapping = SubstrateBlockHashMapping<Self>;
+	type CallOrigin = EnsureAddressRoot<u64>;
+	type WithdrawOrigin = EnsureAddressNever<u64>;
+	type AddressMapping = MockAddressMapping;
+	type Currency = Balances;
+	type Event = Event;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type PrecompilesType = Precompiles;
+	type PrecompilesValue = PrecompilesValue;
+	type ChainId = ();
+	type OnChargeTransaction = ();
+	type BlockGasLimit = ();
+	type FindAuthor = ();
+}
+
+parameter_types! {
+	pub const TokenFungiblePalletId: PalletId = PalletId(*b"w3g/tfpi");
+	pub const TokenNonFungiblePalletId: PalletId = PalletId(*b"w3g/tnfp");
+	pub const TokenMultiPalletId: PalletId = PalletId(*b"w3g/tmpi");
+	pub const StringLimit: u32 = 50;
+	pub const MetadataOverflow: pallet_token_fungible::MetadataOverflowPolicy =
+		pallet_token_fungible::MetadataOverflowPolicy::Reject;
+	pub const FeeRounding: pallet_token_fungible::RoundingMode =
+		pallet_token_fungible::RoundingMode::Floor;
+	pub const MaxApprovalsPerOwner: Option<u32> = None;
+	pub const MintFee: Balance = 0;
+	pub const CreateTokenDeposit: Balance = 500 * MILLICENTS;
+}
+
+/// Token lotteries aren't run through the precompiles.
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		(H256::zero(), 0)
+	}
+}
+
+impl pallet_token_fungible::Config for Test {
+	type Event = Event;
+	type PalletId = TokenFungiblePalletId;
+	type FungibleTokenId = u128;
+	type StringLimit = StringLimit;
+	type CreateTokenDeposit = CreateTokenDeposit;
+	type Currency = Balances;
+	type MaxBatchTransfers = ConstU32<10>;
+	type OnMetadataOverflow = MetadataOverflow;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type InactivityThreshold = ConstU64<100>;
+	type NormalizeSymbols = ConstBool<false>;
+	type DefaultDecimals = ConstU8<18>;
+	type EmitBalanceChanged = ConstBool<false>;
+	type OnBalanceChanged = ();
+	type MintFee = MintFee;
+	type Randomness = TestRandomness;
+	type MaxLotteryHolders = ConstU32<100>;
+	type MaxBatchMints = ConstU32<10>;
+	type UnsafeRecipients = Nothing;
+	type MinNameLength = ConstU32<1>;
+	type MinSymbolLength = ConstU32<2>;
+	type MaxApprovalsPerOwner = MaxApprovalsPerOwner;
+	type MaxEmissions = ConstU32<10>;
+	type BridgeOrigin = frame_system::EnsureRoot<u64>;
+	type MaxFeeSplits = ConstU32<4>;
+	type FeeRounding = FeeRounding;
+	type WeightInfo = ();
+}
+
+impl pallet_token_non_fungible::Config for Test {
+	type Event = Event;
+	type PalletId = TokenNonFungiblePalletId;
+	type NonFungibleTokenId = u128;
+	type TokenId = u128;
+	type StringLimit = StringLimit;
+	type CreateTokenDeposit = CreateTokenDeposit;
+	type Currency = Balances;
+	type WeightInfo = ();
+}
+
+impl pallet_token_multi::Config for Test {
+	type Event = Event;
+	type PalletId = TokenMultiPalletId;
+	type MultiTokenId = u128;
+	type TokenId = u128;
+	type StringLimit = StringLimit;
+	type CreateTokenDeposit = CreateTokenDeposit;
+	type Currency = Balances;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100 * DOLLARS), (2, 100 * DOLLARS)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,1123 @@
+// This file is part of Web3Games.
+
+// Copyright (C) 2021-2022 Web3Games https://web3games.org
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	mock::*,
+	token_fungible::{
+		Action as FungibleAction, EIP1271_MAGIC_VALUE, PERMIT_DOMAIN, PERMIT_TYPEHASH,
+		SELECTOR_LOG_APPROVAL as SELECTOR_LOG_FUNGIBLE_APPROVAL, SELECTOR_ON_TOKEN_TRANSFER,
+	},
+	token_multi::Action as MultiAction,
+	token_non_fungible::{
+		Action as NonFungibleAction, SELECTOR_LOG_APPROVAL as SELECTOR_LOG_NON_FUNGIBLE_APPROVAL,
+		SELECTOR_LOG_TRANSFER as SELECTOR_LOG_NON_FUNGIBLE_TRANSFER,
+	},
+	TOKEN_NON_FUNGIBLE_CREATE_SELECTOR,
+};
+use fp_evm::{ExitReason, ExitRevert, ExitSucceed};
+use frame_support::{assert_ok, storage::with_transaction};
+use pallet_evm::{AddressMapping, Context, PrecompileResult, PrecompileSet};
+use pallet_support::NonFungibleEnumerable;
+use precompile_utils::{prelude::*, testing::*};
+use sp_core::{ecdsa, Pair, H160, H256, U256};
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_runtime::{DispatchError, TransactionOutcome};
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+const CHARLIE: u64 = 3;
+
+fn precompiles() -> Precompiles {
+	Precompiles
+}
+
+fn selector(bytes: &[u8]) -> u32 {
+	let mut data = [0u8; 4];
+	data.copy_from_slice(bytes);
+	u32::from_be_bytes(data)
+}
+
+fn returns_true() -> Vec<u8> {
+	EvmDataWriter::new().write(true).build()
+}
+
+/// Run `input` at `address` in a static frame, which the tester can't set up.
+fn execute_static(caller: H160, address: H160, input: Vec<u8>) -> Option<PrecompileResult> {
+	let mut handle =
+		MockHandle::new(address, Context { address, caller, apparent_value: U256::zero() });
+	handle.input = input;
+	handle.is_static = true;
+	precompiles().execute(&mut handle)
+}
+
+/// Run `f` in a storage layer rolled back afterwards, like the EVM does with the frame of a
+/// reverted call.
+fn in_reverted_frame(f: impl FnOnce()) {
+	let _ = with_transaction(|| {
+		f();
+		TransactionOutcome::Rollback(Ok::<_, DispatchError>(()))
+	});
+}
+
+/// Whether `output` is the revert of a dispatched call failing with `error`.
+fn dispatch_failed_with(error: &'static str) -> impl Fn(&[u8]) -> bool {
+	move |output| {
+		let output = core::str::from_utf8(output).unwrap_or_default();
+		output.starts_with("Dispatched call failed") && output.contains(error)
+	}
+}
+
+/// Fungible token 1 with 18 decimals, owned by `ALICE` who holds 1_000 of it.
+fn create_token() {
+	assert_ok!(TokenFungible::create_token(
+		Origin::signed(ALICE),
+		1,
+		b"W3G".to_vec(),
+		b"W3G".to_vec(),
+		18,
+	));
+	assert_ok!(TokenFungible::mint(Origin::signed(ALICE), 1, ALICE, 1_000));
+}
+
+/// Collection 1, owned by `ALICE`.
+fn create_collection() {
+	assert_ok!(TokenNonFungible::create_token(
+		Origin::signed(ALICE),
+		1,
+		b"W3G".to_vec(),
+		b"W3G".to_vec(),
+		b"https://web3games.com/".to_vec(),
+	));
+}
+
+fn create_collection_input() -> Vec<u8> {
+	EvmDataWriter::new_with_selector(selector(TOKEN_NON_FUNGIBLE_CREATE_SELECTOR))
+		.write(Bytes::from("W3G"))
+		.write(Bytes::from("W3G"))
+		.write(Bytes::from("https://web3games.com/"))
+		.build()
+}
+
+#[test]
+fn create_collection_should_revert_for_existing_collection() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				non_fungible_token_address(1),
+				create_collection_input(),
+			)
+			.execute_returns(returns_true());
+		assert!(TokenNonFungible::exists(1));
+
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				create_collection_input(),
+			)
+			.execute_reverts(|output| output == b"collection exists");
+		assert_eq!(
+			TokenNonFungible::collection(1).map(|collection| *collection.owner()),
+			Some(ALICE)
+		);
+	});
+}
+
+#[test]
+fn tokens_of_owner_should_page_in_order() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		for token_id in 10..15 {
+			assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, BOB, token_id));
+		}
+
+		let page = |start: u32, limit: u32| {
+			EvmDataWriter::new_with_selector(NonFungibleAction::TokensOfOwner)
+				.write(Address(evm_address(BOB)))
+				.write(start)
+				.write(limit)
+				.build()
+		};
+		let token_ids = |token_ids: Vec<u128>| EvmDataWriter::new().write(token_ids).build();
+
+		for (start, limit, expected) in [
+			(0, 2, vec![10, 11]),
+			(2, 2, vec![12, 13]),
+			(4, 2, vec![14]),
+			(5, 2, vec![]),
+			(0, 0, vec![]),
+			(0, 100, vec![10, 11, 12, 13, 14]),
+			(u32::MAX, 100, vec![]),
+		] {
+			precompiles()
+				.prepare_test(evm_address(ALICE), non_fungible_token_address(1), page(start, limit))
+				.execute_returns(token_ids(expected));
+		}
+
+		precompiles()
+			.prepare_test(evm_address(ALICE), non_fungible_token_address(1), page(0, 101))
+			.execute_reverts(|output| output == b"limit too large");
+	});
+}
+
+/// The address of `pair`, recovered from a signature like `permit` does.
+fn signer_address(pair: &ecdsa::Pair) -> H160 {
+	let message = [0u8; 32];
+	let public = secp256k1_ecdsa_recover(&pair.sign_prehashed(&message).0, &message).unwrap();
+	H160::from(H256::from(keccak_256(&public)))
+}
+
+/// `owner` signing with `pair` a permit of token 1 for `spender`, encoded as a call.
+fn signed_permit(
+	pair: &ecdsa::Pair,
+	owner: H160,
+	spender: H160,
+	value: u128,
+	nonce: u128,
+	deadline: u64,
+) -> Vec<u8> {
+	let domain_separator = keccak_256(
+		&EvmDataWriter::new()
+			.write(H256::from(PERMIT_DOMAIN))
+			.write(H256::from(keccak_256(b"W3G")))
+			.write(H256::from(keccak_256(b"1")))
+			// The mock's chain id.
+			.write(U256::zero())
+			.write(Address(fungible_token_address(1)))
+			.build(),
+	);
+	let permit = keccak_256(
+		&EvmDataWriter::new()
+			.write(H256::from(PERMIT_TYPEHASH))
+			.write(Address(owner))
+			.write(Address(spender))
+			.write(U256::from(value))
+			.write(U256::from(nonce))
+			.write(U256::from(deadline))
+			.build(),
+	);
+	let digest = keccak_256(&[&b"\x19\x01"[..], &domain_separator[..], &permit[..]].concat());
+	let signature = pair.sign_prehashed(&digest).0;
+
+	EvmDataWriter::new_with_selector(FungibleAction::Permit)
+		.write(Address(owner))
+		.write(Address(spender))
+		.write(U256::from(value))
+		.write(U256::from(deadline))
+		// Ethereum's recovery id.
+		.write(signature[64] + 27)
+		.write(H256::from_slice(&signature[0..32]))
+		.write(H256::from_slice(&signature[32..64]))
+		.build()
+}
+
+#[test]
+fn permit_should_work() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		let pair = ecdsa::Pair::from_seed(&[7u8; 32]);
+		let owner = signer_address(&pair);
+		let spender = evm_address(BOB);
+		let permit = signed_permit(&pair, owner, spender, 500, 0, 1_000);
+
+		precompiles()
+			.prepare_test(evm_address(BOB), fungible_token_address(1), permit.clone())
+			.expect_log(log3(
+				fungible_token_address(1),
+				SELECTOR_LOG_FUNGIBLE_APPROVAL,
+				owner,
+				spender,
+				EvmDataWriter::new().write(U256::from(500)).build(),
+			))
+			.execute_returns(returns_true());
+		let owner_account = MockAddressMapping::into_account_id(owner);
+		assert_eq!(TokenFungible::allowances(1, (owner_account, BOB)), 500);
+		assert_eq!(TokenFungible::permit_nonce(1, owner_account), 1);
+
+		// The nonce moved on, so the same signature no longer recovers to the owner.
+		precompiles()
+			.prepare_test(evm_address(BOB), fungible_token_address(1), permit)
+			.execute_reverts(|output| output == b"invalid permit");
+
+		// Signed by another key.
+		let other = ecdsa::Pair::from_seed(&[8u8; 32]);
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				fungible_token_address(1),
+				signed_permit(&other, owner, spender, 700, 1, 1_000),
+			)
+			.execute_reverts(|output| output == b"invalid permit");
+
+		// Deadlines are in seconds, moments in milliseconds.
+		Timestamp::set_timestamp(1_001_000);
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				fungible_token_address(1),
+				signed_permit(&pair, owner, spender, 700, 1, 1_000),
+			)
+			.execute_reverts(|output| output == b"permit expired");
+		assert_eq!(TokenFungible::allowances(1, (owner_account, BOB)), 500);
+	});
+}
+
+#[test]
+fn transfer_from_should_clear_the_approval() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+		assert_ok!(TokenNonFungible::approve(Origin::signed(ALICE), 1, BOB, 7));
+
+		let transfer_from = |from: u64, to: u64| {
+			EvmDataWriter::new_with_selector(NonFungibleAction::TransferFrom)
+				.write(Address(evm_address(from)))
+				.write(Address(evm_address(to)))
+				.write(7u128)
+				.build()
+		};
+
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				transfer_from(ALICE, CHARLIE),
+			)
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_APPROVAL,
+				evm_address(ALICE),
+				H160::zero(),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_TRANSFER,
+				evm_address(ALICE),
+				evm_address(CHARLIE),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.execute_returns(returns_true());
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(CHARLIE));
+		assert_eq!(TokenNonFungible::get_approved(1, 7), None);
+
+		// The approval was given by the previous owner and doesn't carry over.
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				transfer_from(CHARLIE, BOB),
+			)
+			.execute_reverts(dispatch_failed_with("NotOwnerOrApproved"));
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(CHARLIE));
+	});
+}
+
+#[test]
+fn approve_should_emit_the_event_and_the_log() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::Approve)
+					.write(Address(evm_address(BOB)))
+					.write(U256::from(300))
+					.build(),
+			)
+			.expect_log(log3(
+				fungible_token_address(1),
+				SELECTOR_LOG_FUNGIBLE_APPROVAL,
+				evm_address(ALICE),
+				evm_address(BOB),
+				EvmDataWriter::new().write(U256::from(300)).build(),
+			))
+			.execute_returns(returns_true());
+		System::assert_last_event(Event::TokenFungible(pallet_token_fungible::Event::Approval(
+			1, ALICE, BOB, 300,
+		)));
+
+		// Without an EVM frame there is no log to mirror the event with.
+		System::reset_events();
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, CHARLIE, 200));
+		assert_eq!(
+			System::events().into_iter().map(|record| record.event).collect::<Vec<_>>(),
+			vec![Event::TokenFungible(pallet_token_fungible::Event::Approval(
+				1, ALICE, CHARLIE, 200
+			))]
+		);
+	});
+}
+
+#[test]
+fn transfer_from_should_revert_to_the_zero_address() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::TransferFrom)
+					.write(Address(evm_address(ALICE)))
+					.write(Address(H160::zero()))
+					.write(7u128)
+					.build(),
+			)
+			.expect_no_logs()
+			.execute_reverts(|output| output == b"transfer to the zero address");
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(ALICE));
+		assert_eq!(TokenNonFungible::balance_of(1, ALICE), 1);
+		assert_eq!(TokenNonFungible::total_supply(1), 1);
+	});
+}
+
+#[test]
+fn static_calls_should_only_run_views() {
+	new_test_ext().execute_with(|| {
+		create_token();
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+
+		let static_call = Some(Err(revert("static call")));
+		for input in [
+			EvmDataWriter::new_with_selector(NonFungibleAction::TransferFrom)
+				.write(Address(evm_address(ALICE)))
+				.write(Address(evm_address(BOB)))
+				.write(7u128)
+				.build(),
+			EvmDataWriter::new_with_selector(NonFungibleAction::Mint)
+				.write(Address(evm_address(BOB)))
+				.write(8u128)
+				.build(),
+			EvmDataWriter::new_with_selector(NonFungibleAction::Burn).write(7u128).build(),
+		] {
+			assert_eq!(
+				execute_static(evm_address(ALICE), non_fungible_token_address(1), input),
+				static_call
+			);
+		}
+		assert_eq!(
+			execute_static(
+				evm_address(ALICE),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::Transfer)
+					.write(Address(evm_address(BOB)))
+					.write(U256::from(100))
+					.build(),
+			),
+			static_call
+		);
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(ALICE));
+		assert!(!TokenNonFungible::token_exists(1, 8));
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 1_000);
+
+		assert_eq!(
+			execute_static(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::OwnerOf)
+					.write(7u128)
+					.build(),
+			),
+			Some(Ok(succeed(EvmDataWriter::new().write(Address(evm_address(ALICE))).build())))
+		);
+		assert_eq!(
+			execute_static(
+				evm_address(BOB),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::BalanceOf)
+					.write(Address(evm_address(ALICE)))
+					.build(),
+			),
+			Some(Ok(succeed(EvmDataWriter::new().write(1_000u128).build())))
+		);
+	});
+}
+
+#[test]
+fn change_allowance_should_log_the_resulting_allowance() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		let change_allowance = |action: FungibleAction, amount: u128| {
+			EvmDataWriter::new_with_selector(action)
+				.write(Address(evm_address(BOB)))
+				.write(U256::from(amount))
+				.build()
+		};
+		let approval = |allowance: u128| {
+			log3(
+				fungible_token_address(1),
+				SELECTOR_LOG_FUNGIBLE_APPROVAL,
+				evm_address(ALICE),
+				evm_address(BOB),
+				EvmDataWriter::new().write(U256::from(allowance)).build(),
+			)
+		};
+
+		for (action, amount, allowance) in [
+			(FungibleAction::IncreaseAllowance, 300, 300),
+			(FungibleAction::IncreaseAllowance, 200, 500),
+			(FungibleAction::DecreaseAllowance, 150, 350),
+		] {
+			precompiles()
+				.prepare_test(
+					evm_address(ALICE),
+					fungible_token_address(1),
+					change_allowance(action, amount),
+				)
+				.expect_log(approval(allowance))
+				.execute_returns(returns_true());
+		}
+
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				fungible_token_address(1),
+				change_allowance(FungibleAction::DecreaseAllowance, 400),
+			)
+			.expect_no_logs()
+			.execute_reverts(dispatch_failed_with("AllowanceBelowZero"));
+
+		precompiles()
+			.prepare_test(
+				evm_address(CHARLIE),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::Allowance)
+					.write(Address(evm_address(ALICE)))
+					.write(Address(evm_address(BOB)))
+					.build(),
+			)
+			.execute_returns(EvmDataWriter::new().write(350u128).build());
+	});
+}
+
+#[test]
+fn out_of_range_indices_and_ids_should_revert() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+
+		let too_big_index = U256::from(u32::MAX) + 1;
+		let too_big_token_id = U256::from(u128::MAX) + 1;
+		for (input, reason) in [
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenByIndex)
+					.write(too_big_index)
+					.build(),
+				&b"value too big for u32"[..],
+			),
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenOfOwnerByIndex)
+					.write(Address(evm_address(ALICE)))
+					.write(too_big_index)
+					.build(),
+				&b"value too big for u32"[..],
+			),
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenURI)
+					.write(too_big_token_id)
+					.build(),
+				&b"value too big for u128"[..],
+			),
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::OwnerOf)
+					.write(too_big_token_id)
+					.build(),
+				&b"value too big for u128"[..],
+			),
+			// In range, but past the tokens there are.
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenByIndex)
+					.write(1u32)
+					.build(),
+				&b"index out of bounds"[..],
+			),
+			(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenOfOwnerByIndex)
+					.write(Address(evm_address(ALICE)))
+					.write(1u32)
+					.build(),
+				&b"index out of bounds"[..],
+			),
+		] {
+			precompiles()
+				.prepare_test(evm_address(ALICE), non_fungible_token_address(1), input)
+				.execute_reverts(|output| output == reason);
+		}
+	});
+}
+
+#[test]
+fn metadata_should_revert_for_nonexistent_token() {
+	new_test_ext().execute_with(|| {
+		for action in [FungibleAction::Name, FungibleAction::Symbol, FungibleAction::Decimals] {
+			precompiles()
+				.prepare_test(
+					evm_address(ALICE),
+					fungible_token_address(9),
+					EvmDataWriter::new_with_selector(action).build(),
+				)
+				.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost())
+				.execute_reverts(|output| output == b"nonexistent token");
+		}
+	});
+}
+
+#[test]
+fn royalty_info_should_round_down() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+
+		let royalty_info = |sale_price: u128| {
+			EvmDataWriter::new_with_selector(NonFungibleAction::RoyaltyInfo)
+				.write(7u128)
+				.write(U256::from(sale_price))
+				.build()
+		};
+		let royalty = |receiver: H160, amount: u128| {
+			EvmDataWriter::new().write(Address(receiver)).write(U256::from(amount)).build()
+		};
+
+		precompiles()
+			.prepare_test(evm_address(BOB), non_fungible_token_address(1), royalty_info(10_000))
+			.execute_returns(royalty(H160::zero(), 0));
+
+		// 2.5%
+		assert_ok!(TokenNonFungible::set_royalty(Origin::signed(ALICE), 1, CHARLIE, 250));
+		for (sale_price, amount) in [
+			(10_000, 250),
+			(1, 0),
+			(39, 0),
+			(41, 1),
+			(1_000_001, 25_000),
+			(u128::MAX, 8_507_059_173_023_461_586_584_365_185_794_205_286),
+		] {
+			precompiles()
+				.prepare_test(
+					evm_address(BOB),
+					non_fungible_token_address(1),
+					royalty_info(sale_price),
+				)
+				.execute_returns(royalty(evm_address(CHARLIE), amount));
+		}
+	});
+}
+
+#[test]
+fn supports_interface_should_report_royalties() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+
+		let supports_interface = |interface_id: [u8; 4]| {
+			let mut word = [0u8; 32];
+			word[0..4].copy_from_slice(&interface_id);
+			EvmDataWriter::new_with_selector(NonFungibleAction::SupportsInterface)
+				.write(H256::from(word))
+				.build()
+		};
+
+		// ERC-721 itself isn't, its operator approvals and safe transfers aren't routed.
+		for (interface_id, supported) in [
+			([0x2a, 0x55, 0x20, 0x5a], true),
+			([0x01, 0xff, 0xc9, 0xa7], true),
+			([0x5b, 0x5e, 0x13, 0x9f], true),
+			([0x80, 0xac, 0x58, 0xcd], false),
+			([0xff; 4], false),
+		] {
+			precompiles()
+				.prepare_test(
+					evm_address(BOB),
+					non_fungible_token_address(1),
+					supports_interface(interface_id),
+				)
+				.execute_returns(EvmDataWriter::new().write(supported).build());
+		}
+	});
+}
+
+#[test]
+fn multicall_should_be_atomic() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 8));
+
+		let approve = |token_id: u128| {
+			Bytes(
+				EvmDataWriter::new_with_selector(NonFungibleAction::Approve)
+					.write(Address(evm_address(BOB)))
+					.write(token_id)
+					.build(),
+			)
+		};
+		let transfer_from = |token_id: u128| {
+			Bytes(
+				EvmDataWriter::new_with_selector(NonFungibleAction::TransferFrom)
+					.write(Address(evm_address(ALICE)))
+					.write(Address(evm_address(CHARLIE)))
+					.write(token_id)
+					.build(),
+			)
+		};
+		let multicall = |calls: Vec<Bytes>| {
+			EvmDataWriter::new_with_selector(NonFungibleAction::Multicall)
+				.write(calls)
+				.build()
+		};
+
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				non_fungible_token_address(1),
+				multicall(vec![approve(7), transfer_from(7)]),
+			)
+			.execute_returns(
+				EvmDataWriter::new()
+					.write(vec![Bytes(returns_true()), Bytes(returns_true())])
+					.build(),
+			);
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(CHARLIE));
+		assert_eq!(TokenNonFungible::get_approved(1, 7), None);
+
+		// Token 9 doesn't exist, so the batch reverts and the approval before it is undone.
+		in_reverted_frame(|| {
+			precompiles()
+				.prepare_test(
+					evm_address(ALICE),
+					non_fungible_token_address(1),
+					multicall(vec![approve(8), transfer_from(9)]),
+				)
+				.execute_reverts(dispatch_failed_with("TokenNonExistent"));
+		});
+		assert_eq!(TokenNonFungible::owner_of(1, 8), Some(ALICE));
+		assert_eq!(TokenNonFungible::get_approved(1, 8), None);
+	});
+}
+
+#[test]
+fn mint_with_uri_should_set_the_token_uri() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+
+		let precompiles = precompiles();
+		let token_uri = || {
+			precompiles.prepare_test(
+				evm_address(CHARLIE),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::TokenURI)
+					.write(7u128)
+					.build(),
+			)
+		};
+		let uri = |uri: &str| EvmDataWriter::new().write(Bytes::from(uri)).build();
+
+		precompiles
+			.prepare_test(
+				evm_address(ALICE),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::MintWithURI)
+					.write(Address(evm_address(BOB)))
+					.write(7u128)
+					.write(Bytes::from("ipfs://seven"))
+					.build(),
+			)
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_TRANSFER,
+				H160::zero(),
+				evm_address(BOB),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.execute_returns(returns_true());
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(BOB));
+		token_uri().execute_returns(uri("ipfs://seven"));
+
+		precompiles
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::Burn).write(7u128).build(),
+			)
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_APPROVAL,
+				evm_address(BOB),
+				H160::zero(),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_TRANSFER,
+				evm_address(BOB),
+				H160::zero(),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.execute_returns(returns_true());
+		token_uri().execute_reverts(|output| output == b"token not found");
+
+		// The uri went with the burnt token, a new token 7 gets the base uri.
+		precompiles
+			.prepare_test(
+				evm_address(ALICE),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::Mint)
+					.write(Address(evm_address(BOB)))
+					.write(7u128)
+					.build(),
+			)
+			.expect_log(log4(
+				non_fungible_token_address(1),
+				SELECTOR_LOG_NON_FUNGIBLE_TRANSFER,
+				H160::zero(),
+				evm_address(BOB),
+				H256::from_low_u64_be(7),
+				vec![],
+			))
+			.execute_returns(returns_true());
+		assert_eq!(TokenNonFungible::owner_of(1, 7), Some(BOB));
+		token_uri().execute_returns(uri("https://web3games.com/7"));
+	});
+}
+
+#[test]
+fn metadata_views_should_read_the_collection_once() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+
+		let name = EvmDataWriter::new_with_selector(NonFungibleAction::Name).build();
+		let symbol = EvmDataWriter::new_with_selector(NonFungibleAction::Symbol).build();
+		let w3g = EvmDataWriter::new().write(Bytes::from("W3G")).build();
+
+		for input in [name.clone(), symbol.clone()] {
+			precompiles()
+				.prepare_test(evm_address(BOB), non_fungible_token_address(1), input)
+				.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost())
+				.execute_returns(w3g.clone());
+		}
+
+		// Calls of a batch share the read.
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				EvmDataWriter::new_with_selector(NonFungibleAction::Multicall)
+					.write(vec![Bytes(name), Bytes(symbol)])
+					.build(),
+			)
+			.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost())
+			.execute_returns(
+				EvmDataWriter::new().write(vec![Bytes(w3g.clone()), Bytes(w3g)]).build(),
+			);
+	});
+}
+
+#[test]
+fn permit_should_ask_contract_wallets() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		let wallet = evm_address(CHARLIE);
+		pallet_evm::AccountCodes::<Test>::insert(wallet, vec![0x00]);
+		let spender = evm_address(BOB);
+		// The wallet vouches for the signature, whoever made it.
+		let pair = ecdsa::Pair::from_seed(&[7u8; 32]);
+
+		let precompiles = precompiles();
+		let permit = |vouches: bool| {
+			precompiles
+				.prepare_test(
+					evm_address(BOB),
+					fungible_token_address(1),
+					signed_permit(&pair, wallet, spender, 500, 0, 1_000),
+				)
+				.with_subcall_handle(move |Subcall { address, input, is_static, .. }| {
+					assert_eq!(address, wallet);
+					assert!(is_static);
+					assert_eq!(&input[0..4], &EIP1271_MAGIC_VALUE[..]);
+
+					let mut value = [0u8; 32];
+					if vouches {
+						value[0..4].copy_from_slice(&EIP1271_MAGIC_VALUE);
+					}
+					SubcallOutput {
+						reason: ExitReason::Succeed(ExitSucceed::Returned),
+						output: value.to_vec(),
+						cost: 0,
+						logs: vec![],
+					}
+				})
+		};
+
+		permit(false).execute_reverts(|output| output == b"invalid permit");
+		assert_eq!(TokenFungible::allowances(1, (CHARLIE, BOB)), 0);
+
+		permit(true).execute_returns(returns_true());
+		assert_eq!(TokenFungible::allowances(1, (CHARLIE, BOB)), 500);
+		assert_eq!(TokenFungible::permit_nonce(1, CHARLIE), 1);
+	});
+}
+
+#[test]
+fn decimals_should_be_the_token_decimals() {
+	new_test_ext().execute_with(|| {
+		create_token();
+		assert_ok!(TokenFungible::create_token(
+			Origin::signed(BOB),
+			2,
+			b"USDC".to_vec(),
+			b"USDC".to_vec(),
+			6,
+		));
+
+		let precompiles = precompiles();
+		let decimals = |id: u128| {
+			precompiles
+				.prepare_test(
+					evm_address(ALICE),
+					fungible_token_address(id),
+					EvmDataWriter::new_with_selector(FungibleAction::Decimals).build(),
+				)
+				.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost())
+		};
+
+		decimals(1).execute_returns(EvmDataWriter::new().write(18u8).build());
+		decimals(2).execute_returns(EvmDataWriter::new().write(6u8).build());
+		decimals(3).execute_reverts(|output| output == b"nonexistent token");
+	});
+}
+
+#[test]
+fn scale_amount_should_revert_on_overflow() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		let precompiles = precompiles();
+		let scale_amount = |whole: u128, decimals: u8| {
+			precompiles.prepare_test(
+				evm_address(ALICE),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::ScaleAmount)
+					.write(U256::from(whole))
+					.write(decimals)
+					.build(),
+			)
+		};
+
+		for (whole, decimals, amount) in
+			[(5, 18, 5_000_000_000_000_000_000u128), (123, 6, 123_000_000), (7, 0, 7), (0, 38, 0)]
+		{
+			scale_amount(whole, decimals)
+				.execute_returns(EvmDataWriter::new().write(U256::from(amount)).build());
+		}
+
+		// 10^39 doesn't fit a balance, nor does the largest balance times ten.
+		scale_amount(1, 39).execute_reverts(|output| output == b"amount overflow");
+		scale_amount(u128::MAX, 1).execute_reverts(|output| output == b"amount overflow");
+	});
+}
+
+#[test]
+fn scale_amount_should_work_before_the_token_exists() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				evm_address(ALICE),
+				fungible_token_address(5),
+				EvmDataWriter::new_with_selector(FungibleAction::ScaleAmount)
+					.write(U256::from(1_000))
+					.write(6u8)
+					.build(),
+			)
+			.execute_returns(EvmDataWriter::new().write(U256::from(1_000_000_000)).build());
+	});
+}
+
+#[test]
+fn transfer_from_should_tell_balance_from_allowance() {
+	new_test_ext().execute_with(|| {
+		create_token();
+		assert_ok!(TokenFungible::approve(Origin::signed(ALICE), 1, BOB, 1_000));
+		assert_ok!(TokenFungible::transfer(Origin::signed(ALICE), 1, CHARLIE, 800));
+
+		let precompiles = precompiles();
+		let transfer_from = |amount: u128| {
+			precompiles.prepare_test(
+				evm_address(BOB),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::TransferFrom)
+					.write(Address(evm_address(ALICE)))
+					.write(Address(evm_address(CHARLIE)))
+					.write(U256::from(amount))
+					.build(),
+			)
+		};
+
+		// Allowed, but more than is left.
+		transfer_from(500)
+			.execute_reverts(|output| output == b"ERC20: transfer amount exceeds balance");
+		transfer_from(1_500).execute_reverts(|output| output == b"ERC20: insufficient allowance");
+
+		transfer_from(200).execute_returns(returns_true());
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 0);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 1_000);
+		assert_eq!(TokenFungible::allowances(1, (ALICE, BOB)), 800);
+	});
+}
+
+#[test]
+fn token_exists_should_answer_for_missing_tokens() {
+	new_test_ext().execute_with(|| {
+		create_token();
+		create_collection();
+		assert_ok!(TokenMulti::create_token(
+			Origin::signed(ALICE),
+			1,
+			b"https://web3games.com/".to_vec()
+		));
+
+		// Each pays for the one read it makes, whether or not the token exists.
+		for (address, action, exists) in [
+			(fungible_token_address(1), FungibleAction::TokenExists as u32, true),
+			(fungible_token_address(2), FungibleAction::TokenExists as u32, false),
+			(non_fungible_token_address(1), NonFungibleAction::TokenExists as u32, true),
+			(non_fungible_token_address(2), NonFungibleAction::TokenExists as u32, false),
+			(multi_token_address(1), MultiAction::TokenExists as u32, true),
+			(multi_token_address(2), MultiAction::TokenExists as u32, false),
+		] {
+			precompiles()
+				.prepare_test(
+					evm_address(BOB),
+					address,
+					EvmDataWriter::new_with_selector(action).build(),
+				)
+				.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost())
+				.execute_returns(EvmDataWriter::new().write(exists).build());
+		}
+	});
+}
+
+#[test]
+fn transfer_and_call_should_notify_contracts() {
+	new_test_ext().execute_with(|| {
+		create_token();
+
+		let receiver = evm_address(CHARLIE);
+		pallet_evm::AccountCodes::<Test>::insert(receiver, vec![0x00]);
+
+		let precompiles = precompiles();
+		let transfer_and_call = |to: H160, amount: u128| {
+			precompiles.prepare_test(
+				evm_address(ALICE),
+				fungible_token_address(1),
+				EvmDataWriter::new_with_selector(FungibleAction::TransferAndCall)
+					.write(Address(to))
+					.write(U256::from(amount))
+					.write(Bytes::from("hi"))
+					.build(),
+			)
+		};
+		let on_token_transfer = |reason: ExitReason| {
+			move |Subcall { address, input, is_static, .. }| {
+				assert_eq!(address, receiver);
+				assert!(!is_static);
+				assert_eq!(
+					input,
+					EvmDataWriter::new_with_selector(u32::from_be_bytes(
+						SELECTOR_ON_TOKEN_TRANSFER
+					))
+					.write(Address(evm_address(ALICE)))
+					.write(U256::from(300))
+					.write(Bytes::from("hi"))
+					.build()
+				);
+				SubcallOutput { reason: reason.clone(), output: vec![], cost: 0, logs: vec![] }
+			}
+		};
+
+		// Accounts without code aren't called.
+		transfer_and_call(evm_address(BOB), 100).execute_returns(returns_true());
+		assert_eq!(TokenFungible::balance_of(1, BOB), 100);
+
+		transfer_and_call(receiver, 300)
+			.with_subcall_handle(on_token_transfer(ExitReason::Succeed(ExitSucceed::Returned)))
+			.execute_returns(returns_true());
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 300);
+
+		// The transfer goes with the reverted frame.
+		in_reverted_frame(|| {
+			transfer_and_call(receiver, 300)
+				.with_subcall_handle(on_token_transfer(ExitReason::Revert(ExitRevert::Reverted)))
+				.execute_reverts(|output| output == b"ERC677: onTokenTransfer failed");
+		});
+		assert_eq!(TokenFungible::balance_of(1, ALICE), 600);
+		assert_eq!(TokenFungible::balance_of(1, CHARLIE), 300);
+	});
+}
+
+#[test]
+fn owner_of_batch_should_align_owners_with_ids() {
+	new_test_ext().execute_with(|| {
+		create_collection();
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, ALICE, 7));
+		assert_ok!(TokenNonFungible::mint(Origin::signed(ALICE), 1, BOB, 8));
+
+		let owner_of_batch = |token_ids: Vec<u128>| {
+			EvmDataWriter::new_with_selector(NonFungibleAction::OwnerOfBatch)
+				.write(token_ids.into_iter().map(U256::from).collect::<Vec<_>>())
+				.build()
+		};
+
+		// Missing ids are owned by the zero address. The collection read, then one per id.
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				owner_of_batch(vec![8, 9, 7]),
+			)
+			.expect_cost(RuntimeHelper::<Test>::db_read_gas_cost() * 4)
+			.execute_returns(
+				EvmDataWriter::new()
+					.write(vec![
+						Address(evm_address(BOB)),
+						Address(H160::zero()),
+						Address(evm_address(ALICE)),
+					])
+					.build(),
+			);
+
+		precompiles()
+			.prepare_test(
+				evm_address(BOB),
+				non_fungible_token_address(1),
+				owner_of_batch(vec![7; 101]),
+			)
+			.execute_reverts(|output| output == b"too many token ids");
+	});
+}
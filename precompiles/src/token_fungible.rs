@@ -17,20 +17,45 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{FT_PRECOMPILE_ADDRESS_PREFIX, TOKEN_FUNGIBLE_CREATE_SELECTOR};
-use fp_evm::{PrecompileHandle, PrecompileOutput};
-use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use fp_evm::{Context, ExitReason, PrecompileHandle, PrecompileOutput};
+use frame_support::{
+	dispatch::{DispatchError, Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::Get,
+};
 use pallet_evm::{AddressMapping, PrecompileSet};
 use pallet_support::{FungibleMetadata, TokenIdConversion};
 use precompile_utils::prelude::*;
 use primitives::Balance;
-use sp_core::H160;
+use sp_core::{H160, H256, U256};
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
 use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
 
 pub type FungibleTokenIdOf<Runtime> = <Runtime as pallet_token_fungible::Config>::FungibleTokenId;
 
+/// EIP-712 type hash of the permit domain.
+pub const PERMIT_DOMAIN: [u8; 32] = keccak256!(
+	"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+);
+
+/// EIP-2612 type hash of a permit.
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+	"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+);
+
+/// EIP-1271 `isValidSignature(bytes32,bytes)` selector, which is also the value a contract
+/// returns to accept a signature.
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// ERC677 `onTokenTransfer(address,uint256,bytes)` selector, called on contract recipients of
+/// `transferAndCall`.
+pub const SELECTOR_ON_TOKEN_TRANSFER: [u8; 4] = [0xa4, 0xc0, 0xed, 0x36];
+
+/// Solidity selector of the Approval log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
 #[generate_function_selector]
 #[derive(Debug, PartialEq)]
-enum Action {
+pub(crate) enum Action {
 	Name = "name()",
 	Symbol = "symbol()",
 	Decimals = "decimals()",
@@ -39,11 +64,21 @@ enum Action {
 	Allowance = "allowance(address,address)",
 	Transfer = "transfer(address,uint256)",
 	TransferFrom = "transferFrom(address,address,uint256)",
+	TransferAndCall = "transferAndCall(address,uint256,bytes)",
 	Mint = "mint(address,uint256)",
 	Burn = "burn(uint256)",
 	Approve = "approve(address,uint256)",
+	IncreaseAllowance = "increaseAllowance(address,uint256)",
+	DecreaseAllowance = "decreaseAllowance(address,uint256)",
+	Permit = "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+	Nonces = "nonces(address)",
+	DomainSeparator = "DOMAIN_SEPARATOR()",
+	ScaleAmount = "scaleAmount(uint256,uint8)",
+	TokenExists = "tokenExists()",
 }
 
+/// ERC20 interface of fungible tokens. Amounts are always in base units and never scaled by
+/// the token's decimals; `scaleAmount` converts whole units for callers.
 pub struct FungibleTokenExtension<Runtime>(PhantomData<Runtime>);
 
 impl<Runtime> TokenIdConversion<FungibleTokenIdOf<Runtime>> for FungibleTokenExtension<Runtime>
@@ -80,6 +115,8 @@ where
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<pallet_token_fungible::Call<Runtime>>,
 	<Runtime as pallet_token_fungible::Config>::FungibleTokenId: From<u128> + Into<u128>,
+	Runtime: pallet_timestamp::Config,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
 {
 	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<EvmResult<PrecompileOutput>> {
 		let address = handle.code_address();
@@ -91,19 +128,32 @@ where
 						Ok(selector) => selector,
 						Err(e) => return Some(Err(e)),
 					};
-					if let Err(err) = handle.check_function_modifier(match selector {
+					let modifier = match selector {
 						Action::Name |
 						Action::Symbol |
 						Action::Decimals |
 						Action::TotalSupply |
 						Action::Allowance |
+						Action::Nonces |
+						Action::DomainSeparator |
+						Action::ScaleAmount |
+						Action::TokenExists |
 						Action::BalanceOf => FunctionModifier::View,
 						Action::Transfer |
 						Action::TransferFrom |
+						Action::TransferAndCall |
 						Action::Mint |
 						Action::Burn |
-						Action::Approve => FunctionModifier::NonPayable,
-					}) {
+						Action::Approve |
+						Action::IncreaseAllowance |
+						Action::DecreaseAllowance |
+						Action::Permit => FunctionModifier::NonPayable,
+					};
+					// `callStatic` simulations must not dispatch anything.
+					if modifier != FunctionModifier::View && handle.is_static() {
+						return Some(Err(revert("static call")))
+					}
+					if let Err(err) = handle.check_function_modifier(modifier) {
 						return Some(Err(err))
 					}
 					match selector {
@@ -120,15 +170,43 @@ where
 						Action::Burn => Self::burn(fungible_token_id, handle),
 						Action::Transfer => Self::transfer(fungible_token_id, handle),
 						Action::TransferFrom => Self::transfer_from(fungible_token_id, handle),
+						// ERC677
+						Action::TransferAndCall =>
+							Self::transfer_and_call(fungible_token_id, handle),
 						Action::Approve => Self::approve(fungible_token_id, handle),
+						Action::IncreaseAllowance =>
+							Self::increase_allowance(fungible_token_id, handle),
+						Action::DecreaseAllowance =>
+							Self::decrease_allowance(fungible_token_id, handle),
+						// EIP-2612
+						Action::Permit => Self::permit(fungible_token_id, handle),
+						Action::Nonces => Self::nonces(fungible_token_id, handle),
+						Action::DomainSeparator =>
+							Self::domain_separator(fungible_token_id, handle),
+						Action::ScaleAmount => Self::scale_amount(handle),
+						Action::TokenExists => Self::token_exists(fungible_token_id, handle),
 					}
 				};
 				return Some(result)
 			} else {
 				if &input[0..4] == TOKEN_FUNGIBLE_CREATE_SELECTOR {
+					if handle.is_static() {
+						return Some(Err(revert("static call")))
+					}
 					let result = Self::create(fungible_token_id, handle);
 					return Some(result)
 				}
+				match handle.read_selector() {
+					Ok(Action::TokenExists) =>
+						return Some(Self::token_exists(fungible_token_id, handle)),
+					// The metadata getters revert, so probes don't read a missing token as empty.
+					Ok(Action::Name) => return Some(Self::name(fungible_token_id, handle)),
+					Ok(Action::Symbol) => return Some(Self::symbol(fungible_token_id, handle)),
+					Ok(Action::Decimals) => return Some(Self::decimals(fungible_token_id, handle)),
+					// Needs no token, callers size the supply of one they are about to create.
+					Ok(Action::ScaleAmount) => return Some(Self::scale_amount(handle)),
+					_ => {},
+				}
 			}
 		}
 		None
@@ -155,6 +233,8 @@ where
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	Runtime::Call: From<pallet_token_fungible::Call<Runtime>>,
 	<Runtime as pallet_token_fungible::Config>::FungibleTokenId: From<u128> + Into<u128>,
+	Runtime: pallet_timestamp::Config,
+	<Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
 {
 	fn create(
 		id: FungibleTokenIdOf<Runtime>,
@@ -228,6 +308,44 @@ where
 	fn approve(
 		id: FungibleTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		Self::change_allowance(id, handle, |spender, amount| {
+			pallet_token_fungible::Call::<Runtime>::approve { id, spender, amount }
+		})
+	}
+
+	fn increase_allowance(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		Self::change_allowance(id, handle, |spender, added_value| pallet_token_fungible::Call::<
+			Runtime,
+		>::increase_allowance {
+			id,
+			spender,
+			added_value,
+		})
+	}
+
+	fn decrease_allowance(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		Self::change_allowance(id, handle, |spender, subtracted_value| {
+			pallet_token_fungible::Call::<Runtime>::decrease_allowance {
+				id,
+				spender,
+				subtracted_value,
+			}
+		})
+	}
+
+	/// Dispatch an allowance change built by `call` from the `(spender, amount)` arguments
+	/// and emit the `Approval` log carrying the resulting allowance.
+	fn change_allowance(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+		call: impl FnOnce(Runtime::AccountId, Balance) -> pallet_token_fungible::Call<Runtime>,
 	) -> EvmResult<PrecompileOutput> {
 		let mut input = handle.read_input()?;
 		input.expect_arguments(2)?;
@@ -235,7 +353,10 @@ where
 		let spender: H160 = input.read::<Address>()?.into();
 		let amount = input.read::<Balance>()?;
 
-		{
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		handle.record_log_costs_manual(3, 32)?;
+
+		let allowance = {
 			let caller: Runtime::AccountId =
 				Runtime::AddressMapping::into_account_id(handle.context().caller);
 			let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
@@ -243,15 +364,184 @@ where
 			// Dispatch call (if enough gas).
 			RuntimeHelper::<Runtime>::try_dispatch(
 				handle,
-				Some(caller).into(),
-				pallet_token_fungible::Call::<Runtime>::approve { id, spender, amount },
+				Some(caller.clone()).into(),
+				call(spender.clone(), amount),
 			)?;
-		}
+
+			pallet_token_fungible::Pallet::<Runtime>::allowances(id, (caller, spender))
+		};
+
+		// The pallet has deposited `Event::Approval`; mirror it as the ERC20 log so EVM
+		// indexers see the same approval.
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			handle.context().caller,
+			spender,
+			EvmDataWriter::new().write(U256::from(allowance)).build(),
+		)
+		.record(handle)?;
 
 		// Return call information
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
 
+	fn permit(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		// Timestamp, token name, nonce, owner code and allowance reads; allowance and nonce
+		// writes.
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(5))?;
+		handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost().saturating_mul(2))?;
+
+		let mut input = handle.read_input()?;
+		input.expect_arguments(7)?;
+
+		let owner: H160 = input.read::<Address>()?.into();
+		let spender: H160 = input.read::<Address>()?.into();
+		let value = input.read::<Balance>()?;
+		let deadline = input.read::<U256>()?;
+		let v = input.read::<u8>()?;
+		let r = input.read::<H256>()?;
+		let s = input.read::<H256>()?;
+
+		// Moments are in milliseconds, deadlines in seconds.
+		let now: U256 = pallet_timestamp::Pallet::<Runtime>::get().into();
+		if deadline < now / 1000 {
+			return Err(revert("permit expired"))
+		}
+
+		let owner_account: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+		let nonce: U256 =
+			pallet_token_fungible::Pallet::<Runtime>::permit_nonce(id, &owner_account).into();
+
+		let permit = keccak_256(
+			&EvmDataWriter::new()
+				.write(H256::from(PERMIT_TYPEHASH))
+				.write(Address(owner))
+				.write(Address(spender))
+				.write(U256::from(value))
+				.write(nonce)
+				.write(deadline)
+				.build(),
+		);
+		let mut message = Vec::with_capacity(66);
+		message.extend_from_slice(b"\x19\x01");
+		message.extend_from_slice(&Self::compute_domain_separator(id, handle.code_address()));
+		message.extend_from_slice(&permit);
+		let digest = keccak_256(&message);
+
+		let mut signature = [0u8; 65];
+		signature[0..32].copy_from_slice(r.as_bytes());
+		signature[32..64].copy_from_slice(s.as_bytes());
+		signature[64] = v;
+
+		// Contract wallets can't sign, they vouch for signatures through EIP-1271 instead.
+		let valid = if pallet_evm::AccountCodes::<Runtime>::contains_key(owner) {
+			Self::is_valid_contract_signature(handle, owner, digest, &signature)?
+		} else {
+			secp256k1_ecdsa_recover(&signature, &digest)
+				.map(|signer| H160::from(H256::from(keccak_256(&signer))) == owner)
+				.unwrap_or(false)
+		};
+		if !valid {
+			return Err(revert("invalid permit"))
+		}
+
+		handle.record_log_costs_manual(3, 32)?;
+
+		let spender_account: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
+		pallet_token_fungible::Pallet::<Runtime>::do_permit(
+			id,
+			&owner_account,
+			&spender_account,
+			value,
+		)
+		.map_err(|e| revert(<&'static str>::from(e)))?;
+
+		let allowance = pallet_token_fungible::Pallet::<Runtime>::allowances(
+			id,
+			(owner_account, spender_account),
+		);
+		log3(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			owner,
+			spender,
+			EvmDataWriter::new().write(U256::from(allowance)).build(),
+		)
+		.record(handle)?;
+
+		Ok(succeed(EvmDataWriter::new().write(true).build()))
+	}
+
+	/// Ask the `owner` contract through a static EIP-1271 `isValidSignature` call whether
+	/// it accepts `signature` for `digest`.
+	fn is_valid_contract_signature(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		digest: [u8; 32],
+		signature: &[u8],
+	) -> EvmResult<bool> {
+		let input = EvmDataWriter::new_with_selector(u32::from_be_bytes(EIP1271_MAGIC_VALUE))
+			.write(H256::from(digest))
+			.write(Bytes::from(signature))
+			.build();
+		let context =
+			Context { address: owner, caller: handle.code_address(), apparent_value: U256::zero() };
+		let gas = handle.remaining_gas();
+
+		let (reason, output) = handle.call(owner, None, input, Some(gas), true, &context);
+
+		Ok(matches!(reason, ExitReason::Succeed(_)) &&
+			output.len() >= 4 &&
+			output[0..4] == EIP1271_MAGIC_VALUE)
+	}
+
+	fn nonces(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		let mut input = handle.read_input()?;
+		input.expect_arguments(1)?;
+
+		let owner: H160 = input.read::<Address>()?.into();
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
+
+		let nonce: U256 = pallet_token_fungible::Pallet::<Runtime>::permit_nonce(id, owner).into();
+
+		Ok(succeed(EvmDataWriter::new().write(nonce).build()))
+	}
+
+	fn domain_separator(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		let domain_separator = Self::compute_domain_separator(id, handle.code_address());
+
+		Ok(succeed(EvmDataWriter::new().write(H256::from(domain_separator)).build()))
+	}
+
+	fn compute_domain_separator(id: FungibleTokenIdOf<Runtime>, address: H160) -> [u8; 32] {
+		let name = keccak_256(&pallet_token_fungible::Pallet::<Runtime>::token_name(id));
+		let chain_id: U256 = <Runtime as pallet_evm::Config>::ChainId::get().into();
+
+		keccak_256(
+			&EvmDataWriter::new()
+				.write(H256::from(PERMIT_DOMAIN))
+				.write(H256::from(name))
+				.write(H256::from(keccak256!("1")))
+				.write(chain_id)
+				.write(Address(address))
+				.build(),
+		)
+	}
+
 	fn transfer(
 		id: FungibleTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
@@ -296,6 +586,17 @@ where
 			let from: Runtime::AccountId = Runtime::AddressMapping::into_account_id(from);
 			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
 
+			// Contracts tell a short balance from a short allowance by the revert reason, which
+			// the dispatched call doesn't give, so check both first.
+			handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(6))?;
+			if let Err(e) = pallet_token_fungible::Pallet::<Runtime>::validate_transfer_from(
+				id, &caller, &from, &to, amount,
+			) {
+				if let Some(reason) = Self::erc20_revert_reason(e) {
+					return Err(revert(reason))
+				}
+			}
+
 			// Dispatch call (if enough gas).
 			RuntimeHelper::<Runtime>::try_dispatch(
 				handle,
@@ -313,6 +614,70 @@ where
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
 
+	/// Transfer to `to`, then call `onTokenTransfer(from, value, data)` on it if it is a
+	/// contract. A reverting callback reverts the transfer too.
+	fn transfer_and_call(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let mut input = handle.read_input()?;
+		input.expect_arguments(3)?;
+
+		let to: H160 = input.read::<Address>()?.into();
+		let amount = input.read::<Balance>()?;
+		let data = input.read::<Bytes>()?;
+
+		{
+			let caller: Runtime::AccountId =
+				Runtime::AddressMapping::into_account_id(handle.context().caller);
+			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
+
+			// Dispatch call (if enough gas).
+			RuntimeHelper::<Runtime>::try_dispatch(
+				handle,
+				Some(caller).into(),
+				pallet_token_fungible::Call::<Runtime>::transfer { id, recipient: to, amount },
+			)?;
+		}
+
+		// Recipient code read.
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		if pallet_evm::AccountCodes::<Runtime>::contains_key(to) {
+			let input =
+				EvmDataWriter::new_with_selector(u32::from_be_bytes(SELECTOR_ON_TOKEN_TRANSFER))
+					.write(Address(handle.context().caller))
+					.write(U256::from(amount))
+					.write(data)
+					.build();
+			let context = Context {
+				address: to,
+				caller: handle.code_address(),
+				apparent_value: U256::zero(),
+			};
+			let gas = handle.remaining_gas();
+
+			let (reason, _) = handle.call(to, None, input, Some(gas), false, &context);
+			if !matches!(reason, ExitReason::Succeed(_)) {
+				return Err(revert("ERC677: onTokenTransfer failed"))
+			}
+		}
+
+		Ok(succeed(EvmDataWriter::new().write(true).build()))
+	}
+
+	/// The OpenZeppelin revert reason for pallet errors ERC20 callers tell apart.
+	fn erc20_revert_reason(error: DispatchError) -> Option<&'static str> {
+		use pallet_token_fungible::Error;
+
+		if error == Error::<Runtime>::InsufficientTokens.into() {
+			Some("ERC20: transfer amount exceeds balance")
+		} else if error == Error::<Runtime>::InsufficientAuthorizedTokens.into() {
+			Some("ERC20: insufficient allowance")
+		} else {
+			None
+		}
+	}
+
 	fn mint(
 		id: FungibleTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
@@ -365,11 +730,51 @@ where
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
 
+	/// `whole * 10^decimals`, reverting on overflow. Doesn't depend on the token.
+	fn scale_amount(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+		let mut input = handle.read_input()?;
+		input.expect_arguments(2)?;
+
+		let whole = input.read::<Balance>()?;
+		let decimals = input.read::<u8>()?;
+
+		let amount = pallet_token_fungible::Pallet::<Runtime>::scale_amount(whole, decimals)
+			.ok_or_else(|| revert("amount overflow"))?;
+
+		Ok(succeed(EvmDataWriter::new().write(amount).build()))
+	}
+
+	/// Revert unless `id` is an existing token, so metadata probes don't read as empty. Charges
+	/// the token read, which the metadata getters share.
+	fn ensure_exists(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		if !pallet_token_fungible::Pallet::<Runtime>::exists(id) {
+			return Err(revert("nonexistent token"))
+		}
+		Ok(())
+	}
+
+	/// Whether the token exists, answered at any address of the prefix so contracts can
+	/// branch on it rather than catch a revert.
+	fn token_exists(
+		id: FungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let exists = pallet_token_fungible::Pallet::<Runtime>::exists(id);
+
+		Ok(succeed(EvmDataWriter::new().write(exists).build()))
+	}
+
 	fn name(
 		id: FungibleTokenIdOf<Runtime>,
-		_handle: &mut impl PrecompileHandle,
+		handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
-		// handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		Self::ensure_exists(id, handle)?;
 
 		let name = pallet_token_fungible::Pallet::<Runtime>::token_name(id);
 		// Build output.
@@ -378,8 +783,10 @@ where
 
 	fn symbol(
 		id: FungibleTokenIdOf<Runtime>,
-		_handle: &mut impl PrecompileHandle,
+		handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
+		Self::ensure_exists(id, handle)?;
+
 		let symbol = pallet_token_fungible::Pallet::<Runtime>::token_symbol(id);
 
 		// Build output.
@@ -388,8 +795,11 @@ where
 
 	fn decimals(
 		id: FungibleTokenIdOf<Runtime>,
-		_handle: &mut impl PrecompileHandle,
+		handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
+		Self::ensure_exists(id, handle)?;
+
+		// The token's own decimals, returned as a `uint8`.
 		let decimals: u8 = pallet_token_fungible::Pallet::<Runtime>::token_decimals(id);
 		// Build output.
 		Ok(succeed(EvmDataWriter::new().write(decimals).build()))
@@ -30,7 +30,7 @@ pub type MultiTokenIdOf<Runtime> = <Runtime as pallet_token_multi::Config>::Mult
 
 #[generate_function_selector]
 #[derive(Debug, PartialEq)]
-enum Action {
+pub(crate) enum Action {
 	BalanceOf = "balanceOf(address,uint256)",
 	BalanceOfBatch = "balanceOfBatch(address[],uint256[])",
 	SafeTransferFrom = "safeTransferFrom(address,address,uint256,uint256,bytes)",
@@ -42,6 +42,7 @@ enum Action {
 	URI = "uri(uint256)",
 	SetApprovalForAll = "setApprovalForAll(address,bool)",
 	IsApprovedForAll = "isApprovedForAll(address,address)",
+	TokenExists = "tokenExists()",
 }
 pub struct MultiTokenExtension<Runtime>(PhantomData<Runtime>);
 
@@ -95,6 +96,7 @@ where
 						Action::URI |
 						Action::BalanceOfBatch |
 						Action::IsApprovedForAll |
+						Action::TokenExists |
 						Action::BalanceOf => FunctionModifier::View,
 						Action::SafeTransferFrom |
 						Action::SafeBatchTransferFrom |
@@ -123,6 +125,7 @@ where
 							Self::set_approval_for_all(multi_token_id, handle),
 						Action::IsApprovedForAll =>
 							Self::is_approval_for_all(multi_token_id, handle),
+						Action::TokenExists => Self::token_exists(multi_token_id, handle),
 					}
 				};
 				return Some(result)
@@ -131,6 +134,9 @@ where
 					let result = Self::create(multi_token_id, handle);
 					return Some(result)
 				}
+				if let Ok(Action::TokenExists) = handle.read_selector() {
+					return Some(Self::token_exists(multi_token_id, handle))
+				}
 			}
 		}
 		None
@@ -403,6 +409,18 @@ where
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
 
+	/// Whether the token exists, answered at any address of the prefix so contracts can
+	/// branch on it rather than catch a revert.
+	fn token_exists(
+		id: MultiTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let exists = pallet_token_multi::Pallet::<Runtime>::exists(id);
+
+		Ok(succeed(EvmDataWriter::new().write(exists).build()))
+	}
+
 	fn uri(
 		id: MultiTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
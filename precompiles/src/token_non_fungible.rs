@@ -17,28 +17,53 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{NFT_PRECOMPILE_ADDRESS_PREFIX, TOKEN_NON_FUNGIBLE_CREATE_SELECTOR};
-use fp_evm::PrecompileOutput;
+use fp_evm::{Context, ExitError, ExitReason, PrecompileOutput, Transfer};
 use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
 use pallet_evm::{AddressMapping, PrecompileHandle, PrecompileSet};
-use pallet_support::{
-	AccountMapping, NonFungibleEnumerable, NonFungibleMetadata, TokenIdConversion,
-};
+use pallet_support::{AccountMapping, NonFungibleEnumerable, TokenIdConversion};
 use precompile_utils::prelude::*;
-use primitives::{TokenId, TokenIndex};
-use sp_core::{H160, U256};
+use primitives::{Balance, TokenId, TokenIndex};
+use sp_core::{H160, H256, U256};
 use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
 
 /// Solidity selector of the Transfer log, which is the Keccak of the Log signature.
-// pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
 
+/// Solidity selector of the Approval log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// Id of a collection, encoded in the precompile address.
 pub type NonFungibleTokenIdOf<Runtime> =
 	<Runtime as pallet_token_non_fungible::Config>::NonFungibleTokenId;
 
+/// Id of an item within a collection, always read from the call arguments.
+pub type TokenIdOf<Runtime> = <Runtime as pallet_token_non_fungible::Config>::TokenId;
+
+/// ERC-165 interface ids reported by `supportsInterface`: ERC-165 itself, the ERC-721 metadata
+/// and enumerable extensions, and EIP-2981 royalties. Only ids whose every function is routed
+/// are listed, so ERC-721 itself isn't: there is no `safeTransferFrom` or operator approval.
+pub const SUPPORTED_INTERFACES: [[u8; 4]; 4] = [
+	[0x01, 0xff, 0xc9, 0xa7],
+	[0x5b, 0x5e, 0x13, 0x9f],
+	[0x78, 0x0e, 0x9d, 0x63],
+	[0x2a, 0x55, 0x20, 0x5a],
+];
+
+/// A collection, read once per `execute` and shared by the methods needing its metadata.
+pub type CollectionOf<Runtime> = pallet_token_non_fungible::CollectionOf<Runtime>;
+
+/// Maximum number of token ids returned by a single `tokensOfOwner` call.
+pub const TOKENS_OF_OWNER_LIMIT: TokenIndex = 100;
+
+/// Maximum number of token ids a single `ownerOfBatch` call looks up.
+pub const OWNER_OF_BATCH_LIMIT: usize = 100;
+
 #[generate_function_selector]
 #[derive(Debug, PartialEq)]
-enum Action {
+pub(crate) enum Action {
 	BalanceOf = "balanceOf(address)",
 	OwnerOf = "ownerOf(uint256)",
+	OwnerOfBatch = "ownerOfBatch(uint256[])",
 	TransferFrom = "transferFrom(address,address,uint256)",
 	Mint = "mint(address,uint256)",
 	Burn = "burn(uint256)",
@@ -48,7 +73,65 @@ enum Action {
 	TotalSupply = "totalSupply()",
 	TokenOfOwnerByIndex = "tokenOfOwnerByIndex(address,uint256)",
 	TokenByIndex = "tokenByIndex(uint256)",
+	TokensOfOwner = "tokensOfOwner(address,uint256,uint256)",
 	Approve = "approve(address,uint256)",
+	RoyaltyInfo = "royaltyInfo(uint256,uint256)",
+	SupportsInterface = "supportsInterface(bytes4)",
+	Multicall = "multicall(bytes[])",
+	MintWithURI = "mintWithURI(address,uint256,bytes)",
+	TokenExists = "tokenExists()",
+}
+
+/// Handle of one call in a `multicall` batch: the outer call with the input replaced.
+struct BatchedCallHandle<'a, H> {
+	inner: &'a mut H,
+	input: Vec<u8>,
+}
+
+impl<'a, H: PrecompileHandle> PrecompileHandle for BatchedCallHandle<'a, H> {
+	fn call(
+		&mut self,
+		address: H160,
+		transfer: Option<Transfer>,
+		input: Vec<u8>,
+		target_gas: Option<u64>,
+		is_static: bool,
+		context: &Context,
+	) -> (ExitReason, Vec<u8>) {
+		self.inner.call(address, transfer, input, target_gas, is_static, context)
+	}
+
+	fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+		self.inner.record_cost(cost)
+	}
+
+	fn remaining_gas(&self) -> u64 {
+		self.inner.remaining_gas()
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+		self.inner.log(address, topics, data)
+	}
+
+	fn code_address(&self) -> H160 {
+		self.inner.code_address()
+	}
+
+	fn input(&self) -> &[u8] {
+		&self.input
+	}
+
+	fn context(&self) -> &Context {
+		self.inner.context()
+	}
+
+	fn is_static(&self) -> bool {
+		self.inner.is_static()
+	}
+
+	fn gas_limit(&self) -> Option<u64> {
+		self.inner.gas_limit()
+	}
 }
 
 pub struct NonFungibleTokenExtension<Runtime>(PhantomData<Runtime>);
@@ -96,50 +179,30 @@ where
 		let address = handle.code_address();
 		let input = handle.input();
 		if let Some(non_fungible_token_id) = Self::try_from_address(address) {
-			if pallet_token_non_fungible::Pallet::<Runtime>::exists(non_fungible_token_id) {
-				let result = {
-					let selector = match handle.read_selector() {
-						Ok(selector) => selector,
-						Err(e) => return Some(Err(e)),
-					};
-					if let Err(err) = handle.check_function_modifier(match selector {
-						Action::Name |
-						Action::Symbol |
-						Action::OwnerOf |
-						Action::TotalSupply |
-						Action::TokenURI |
-						Action::TokenOfOwnerByIndex |
-						Action::TokenByIndex |
-						Action::BalanceOf => FunctionModifier::View,
-						Action::TransferFrom | Action::Mint | Action::Burn | Action::Approve =>
-							FunctionModifier::NonPayable,
-					}) {
-						return Some(Err(err))
-					}
-					match selector {
-						// storage getters
-						Action::Name => Self::name(non_fungible_token_id, handle),
-						Action::Symbol => Self::symbol(non_fungible_token_id, handle),
-						Action::TokenURI => Self::token_uri(non_fungible_token_id, handle),
-						Action::TotalSupply => Self::total_supply(non_fungible_token_id, handle),
-						Action::TokenByIndex => Self::token_by_index(non_fungible_token_id, handle),
-						Action::TokenOfOwnerByIndex =>
-							Self::token_of_owner_by_index(non_fungible_token_id, handle),
-						Action::BalanceOf => Self::balance_of(non_fungible_token_id, handle),
-						Action::OwnerOf => Self::owner_of(non_fungible_token_id, handle),
-						// call methods (dispatchable)
-						Action::TransferFrom => Self::transfer_from(non_fungible_token_id, handle),
-						Action::Mint => Self::mint(non_fungible_token_id, handle),
-						Action::Burn => Self::burn(non_fungible_token_id, handle),
-						Action::Approve => Self::approve(non_fungible_token_id, handle),
-					}
+			// Routed whether or not the collection exists, `create` rejects taken ids itself.
+			if input.get(0..4) == Some(TOKEN_NON_FUNGIBLE_CREATE_SELECTOR) {
+				if handle.is_static() {
+					return Some(Err(revert("static call")))
+				}
+				let result = Self::create(non_fungible_token_id, handle);
+				return Some(result)
+			}
+			// The only read of the collection in this call, methods get it passed in.
+			if let Err(e) = handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost()) {
+				return Some(Err(e))
+			}
+			if let Some(collection) =
+				pallet_token_non_fungible::Pallet::<Runtime>::collection(non_fungible_token_id)
+			{
+				let result = match handle.read_selector() {
+					Ok(Action::Multicall) =>
+						Self::multicall(non_fungible_token_id, &collection, handle),
+					Ok(_) => Self::dispatch_action(non_fungible_token_id, &collection, handle),
+					Err(e) => Err(e),
 				};
 				return Some(result)
-			} else {
-				if &input[0..4] == TOKEN_NON_FUNGIBLE_CREATE_SELECTOR {
-					let result = Self::create(non_fungible_token_id, handle);
-					return Some(result)
-				}
+			} else if let Ok(Action::TokenExists) = handle.read_selector() {
+				return Some(Self::token_exists(non_fungible_token_id, handle))
 			}
 		}
 		None
@@ -169,6 +232,106 @@ where
 	<Runtime as pallet_token_non_fungible::Config>::TokenId: From<u128> + Into<u128>,
 	Runtime: AccountMapping<Runtime::AccountId>,
 {
+	/// Run the action selected by the input of `handle`. `multicall` is handled by `execute`,
+	/// so batches can't nest.
+	fn dispatch_action(
+		non_fungible_token_id: NonFungibleTokenIdOf<Runtime>,
+		collection: &CollectionOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let selector = handle.read_selector()?;
+		let modifier = match selector {
+			Action::Name |
+			Action::Symbol |
+			Action::OwnerOf |
+			Action::OwnerOfBatch |
+			Action::TotalSupply |
+			Action::TokenURI |
+			Action::TokenOfOwnerByIndex |
+			Action::TokenByIndex |
+			Action::TokensOfOwner |
+			Action::RoyaltyInfo |
+			Action::SupportsInterface |
+			Action::TokenExists |
+			Action::BalanceOf => FunctionModifier::View,
+			Action::TransferFrom |
+			Action::Mint |
+			Action::Burn |
+			Action::Approve |
+			Action::MintWithURI |
+			Action::Multicall => FunctionModifier::NonPayable,
+		};
+		// `callStatic` simulations must not dispatch anything.
+		if modifier != FunctionModifier::View && handle.is_static() {
+			return Err(revert("static call"))
+		}
+		handle.check_function_modifier(modifier)?;
+		match selector {
+			// storage getters
+			Action::Name => Self::name(collection, handle),
+			Action::Symbol => Self::symbol(collection, handle),
+			Action::TokenURI => Self::token_uri(non_fungible_token_id, collection, handle),
+			Action::TotalSupply => Self::total_supply(non_fungible_token_id, handle),
+			Action::TokenByIndex => Self::token_by_index(non_fungible_token_id, handle),
+			Action::TokenOfOwnerByIndex =>
+				Self::token_of_owner_by_index(non_fungible_token_id, handle),
+			Action::TokensOfOwner => Self::tokens_of_owner(non_fungible_token_id, handle),
+			Action::BalanceOf => Self::balance_of(non_fungible_token_id, handle),
+			Action::OwnerOf => Self::owner_of(non_fungible_token_id, handle),
+			Action::OwnerOfBatch => Self::owner_of_batch(non_fungible_token_id, handle),
+			Action::RoyaltyInfo => Self::royalty_info(non_fungible_token_id, handle),
+			Action::SupportsInterface => Self::supports_interface(handle),
+			Action::TokenExists => Self::token_exists(non_fungible_token_id, handle),
+			// call methods (dispatchable)
+			Action::TransferFrom => Self::transfer_from(non_fungible_token_id, handle),
+			Action::Mint => Self::mint(non_fungible_token_id, handle),
+			Action::MintWithURI => Self::mint_with_uri(non_fungible_token_id, handle),
+			Action::Burn => Self::burn(non_fungible_token_id, handle),
+			Action::Approve => Self::approve(non_fungible_token_id, handle),
+			Action::Multicall => Err(revert("nested multicall")),
+		}
+	}
+
+	/// Run each call of the batch in order against this collection, as if sent on its own
+	/// by the same caller. Gas and logs are accounted on the outer call, and a failing call
+	/// reverts the whole batch, rolling back the calls that ran before it. None of the
+	/// batchable actions change collection metadata, so `collection` stays valid throughout.
+	fn multicall(
+		id: NonFungibleTokenIdOf<Runtime>,
+		collection: &CollectionOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		if handle.is_static() {
+			return Err(revert("static call"))
+		}
+		handle.check_function_modifier(FunctionModifier::NonPayable)?;
+
+		let mut input = handle.read_input()?;
+		input.expect_arguments(1)?;
+		let calls: Vec<Bytes> = input.read()?;
+
+		let mut outputs = Vec::with_capacity(calls.len());
+		for call in calls {
+			let mut batched = BatchedCallHandle { inner: &mut *handle, input: call.0 };
+			let output = Self::dispatch_action(id, collection, &mut batched)?;
+			outputs.push(Bytes(output.output));
+		}
+
+		Ok(succeed(EvmDataWriter::new().write(outputs).build()))
+	}
+
+	/// The collection id comes from the precompile address, so the only item id a call can
+	/// act on is the one in its arguments. Values that don't fit a `TokenId` revert instead of
+	/// being truncated.
+	fn read_token_id(input: &mut EvmDataReader) -> EvmResult<TokenIdOf<Runtime>> {
+		Ok(input.read::<TokenId>()?.into())
+	}
+
+	fn token_id_topic(token_id: TokenIdOf<Runtime>) -> H256 {
+		let token_id: TokenId = token_id.into();
+		H256::from_uint(&U256::from(token_id))
+	}
+
 	fn create(
 		id: NonFungibleTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
@@ -176,6 +339,12 @@ where
 		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
 		input.expect_arguments(3)?;
 
+		// Don't rely on the dispatcher routing existing collections elsewhere.
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		if pallet_token_non_fungible::Pallet::<Runtime>::exists(id) {
+			return Err(revert("collection exists"))
+		}
+
 		let name: Vec<u8> = input.read::<Bytes>()?.into();
 		let symbol: Vec<u8> = input.read::<Bytes>()?.into();
 		let base_uri: Vec<u8> = input.read::<Bytes>()?.into();
@@ -221,15 +390,97 @@ where
 		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
 		input.expect_arguments(1)?;
 
-		let token_id: Runtime::TokenId = input.read::<TokenId>()?.into();
+		let token_id = Self::read_token_id(&mut input)?;
 
 		let owner_account_id: Runtime::AccountId =
-			pallet_token_non_fungible::Pallet::<Runtime>::owner_of(id, token_id).unwrap();
+			pallet_token_non_fungible::Pallet::<Runtime>::owner_of(id, token_id)
+				.ok_or_else(|| revert("token not found"))?;
 		let owner = Runtime::into_evm_address(owner_account_id);
 
 		Ok(succeed(EvmDataWriter::new().write::<Address>(owner.into()).build()))
 	}
 
+	/// The owners of `tokenIds`, in order. Unlike `ownerOf`, missing items don't revert and
+	/// are owned by the zero address, so one bad id doesn't fail the whole snapshot.
+	fn owner_of_batch(
+		id: NonFungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let mut input = handle.read_input()?;
+		input.expect_arguments(1)?;
+		let token_ids: Vec<TokenId> = input.read()?;
+
+		if token_ids.len() > OWNER_OF_BATCH_LIMIT {
+			return Err(revert("too many token ids"))
+		}
+
+		handle.record_cost(
+			RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(token_ids.len() as u64),
+		)?;
+
+		let owners: Vec<Address> = token_ids
+			.into_iter()
+			.map(|token_id| {
+				pallet_token_non_fungible::Pallet::<Runtime>::owner_of(id, token_id.into())
+					.map_or_else(H160::zero, Runtime::into_evm_address)
+					.into()
+			})
+			.collect();
+
+		Ok(succeed(EvmDataWriter::new().write(owners).build()))
+	}
+
+	/// EIP-2981 `royaltyInfo`. The royalty is collection-wide, so the item id is only
+	/// validated. Collections without a royalty owe nothing to the zero address.
+	fn royalty_info(
+		id: NonFungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		let mut input = handle.read_input()?;
+		input.expect_arguments(2)?;
+
+		let _token_id = Self::read_token_id(&mut input)?;
+		let sale_price: Balance = input.read()?;
+
+		let (receiver, amount) =
+			match pallet_token_non_fungible::Pallet::<Runtime>::royalty_info(id, sale_price) {
+				Some((recipient, amount)) => (Runtime::into_evm_address(recipient), amount),
+				None => (H160::zero(), 0),
+			};
+
+		Ok(succeed(
+			EvmDataWriter::new()
+				.write::<Address>(receiver.into())
+				.write::<U256>(amount.into())
+				.build(),
+		))
+	}
+
+	/// Whether the collection exists, answered at any address of the prefix so contracts can
+	/// branch on it rather than catch a revert. `execute` already charged for the read.
+	fn token_exists(
+		id: NonFungibleTokenIdOf<Runtime>,
+		_handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let exists = pallet_token_non_fungible::Pallet::<Runtime>::exists(id);
+
+		Ok(succeed(EvmDataWriter::new().write(exists).build()))
+	}
+
+	fn supports_interface(handle: &mut impl PrecompileHandle) -> EvmResult<PrecompileOutput> {
+		let mut input = handle.read_input()?;
+		input.expect_arguments(1)?;
+
+		// `bytes4` is left-aligned in its word.
+		let word: H256 = input.read()?;
+		let interface_id = &word.as_bytes()[0..4];
+		let supported = SUPPORTED_INTERFACES.iter().any(|id| id[..] == *interface_id);
+
+		Ok(succeed(EvmDataWriter::new().write(supported).build()))
+	}
+
 	fn approve(
 		id: NonFungibleTokenIdOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
@@ -238,9 +489,12 @@ where
 		input.expect_arguments(2)?;
 
 		let spender: H160 = input.read::<Address>()?.into();
-		let token_id = input.read::<TokenId>()?.into();
+		let token_id = Self::read_token_id(&mut input)?;
 
-		{
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		handle.record_log_costs_manual(4, 0)?;
+
+		let owner = {
 			let caller: Runtime::AccountId =
 				Runtime::AddressMapping::into_account_id(handle.context().caller);
 			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
@@ -251,7 +505,21 @@ where
 				Some(caller).into(),
 				pallet_token_non_fungible::Call::<Runtime>::approve { id, to, token_id },
 			)?;
-		}
+
+			pallet_token_non_fungible::Pallet::<Runtime>::owner_of(id, token_id)
+				.map(Runtime::into_evm_address)
+				.unwrap_or_default()
+		};
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			owner,
+			spender,
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
 
 		// Return call information
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
@@ -265,14 +533,21 @@ where
 		input.expect_arguments(3)?;
 		let from: H160 = input.read::<Address>()?.into();
 		let to: H160 = input.read::<Address>()?.into();
-		let token_id = input.read::<TokenId>()?;
+		let token_id = Self::read_token_id(&mut input)?;
+
+		// ERC721 forbids transfers to the zero address, burns must go through `burn`.
+		if to == H160::zero() {
+			return Err(revert("transfer to the zero address"))
+		}
+
+		handle.record_log_costs_manual(4, 0)?;
+		handle.record_log_costs_manual(4, 0)?;
 
 		{
 			let caller: Runtime::AccountId =
 				Runtime::AddressMapping::into_account_id(handle.context().caller);
 			let from: Runtime::AccountId = Runtime::AddressMapping::into_account_id(from);
 			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
-			let token_id: Runtime::TokenId = token_id.into();
 
 			// Dispatch call (if enough gas).
 			RuntimeHelper::<Runtime>::try_dispatch(
@@ -287,6 +562,27 @@ where
 			)?;
 		}
 
+		// ERC721 clears the approval on every transfer, signalled by an approval to zero.
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			from,
+			H160::zero(),
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			from,
+			to,
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
 		// Return call information
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
@@ -299,13 +595,14 @@ where
 		input.expect_arguments(2)?;
 
 		let to: H160 = input.read::<Address>()?.into();
-		let token_id = input.read::<TokenId>()?;
+		let token_id = Self::read_token_id(&mut input)?;
+
+		handle.record_log_costs_manual(4, 0)?;
 
 		{
 			let caller: Runtime::AccountId =
 				Runtime::AddressMapping::into_account_id(handle.context().caller);
 			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
-			let token_id: Runtime::TokenId = token_id.into();
 
 			// Dispatch call (if enough gas).
 			RuntimeHelper::<Runtime>::try_dispatch(
@@ -314,6 +611,62 @@ where
 				pallet_token_non_fungible::Call::<Runtime>::mint { id, to, token_id },
 			)?;
 		}
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			H160::zero(),
+			to,
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
+		// Return call information
+		Ok(succeed(EvmDataWriter::new().write(true).build()))
+	}
+
+	fn mint_with_uri(
+		id: NonFungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let mut input = handle.read_input()?;
+		input.expect_arguments(3)?;
+
+		let to: H160 = input.read::<Address>()?.into();
+		let token_id = Self::read_token_id(&mut input)?;
+		let uri: Bytes = input.read()?;
+
+		handle.record_log_costs_manual(4, 0)?;
+
+		{
+			let caller: Runtime::AccountId =
+				Runtime::AddressMapping::into_account_id(handle.context().caller);
+			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
+
+			// Dispatch call (if enough gas).
+			RuntimeHelper::<Runtime>::try_dispatch(
+				handle,
+				Some(caller).into(),
+				pallet_token_non_fungible::Call::<Runtime>::mint_with_uri {
+					id,
+					to,
+					token_id,
+					uri: uri.0,
+				},
+			)?;
+		}
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			H160::zero(),
+			to,
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
 		// Return call information
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
@@ -325,12 +678,19 @@ where
 		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
 		input.expect_arguments(1)?;
 
-		let token_id = input.read::<TokenId>()?;
+		let token_id = Self::read_token_id(&mut input)?;
+
+		// The owner is gone after the burn, so it is read for the logs first.
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let owner = pallet_token_non_fungible::Pallet::<Runtime>::owner_of(id, token_id)
+			.map_or_else(H160::zero, Runtime::into_evm_address);
+
+		handle.record_log_costs_manual(4, 0)?;
+		handle.record_log_costs_manual(4, 0)?;
 
 		{
 			let caller: Runtime::AccountId =
 				Runtime::AddressMapping::into_account_id(handle.context().caller);
-			let token_id: Runtime::TokenId = token_id.into();
 			// Dispatch call (if enough gas).
 			RuntimeHelper::<Runtime>::try_dispatch(
 				handle,
@@ -338,40 +698,66 @@ where
 				pallet_token_non_fungible::Call::<Runtime>::burn { id, token_id },
 			)?;
 		}
+
+		// Like a transfer, the burn clears the approval.
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_APPROVAL,
+			owner,
+			H160::zero(),
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
+		log4(
+			handle.context().address,
+			SELECTOR_LOG_TRANSFER,
+			owner,
+			H160::zero(),
+			Self::token_id_topic(token_id),
+			vec![],
+		)
+		.record(handle)?;
+
 		// Return call information
 		Ok(succeed(EvmDataWriter::new().write(true).build()))
 	}
 
 	fn name(
-		id: NonFungibleTokenIdOf<Runtime>,
+		collection: &CollectionOf<Runtime>,
 		_handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
-		let name = pallet_token_non_fungible::Pallet::<Runtime>::token_name(id);
+		let name = collection.name();
 
 		// Build output.
 		Ok(succeed(EvmDataWriter::new().write::<Bytes>(name.as_slice().into()).build()))
 	}
 
 	fn symbol(
-		id: NonFungibleTokenIdOf<Runtime>,
+		collection: &CollectionOf<Runtime>,
 		_handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
-		let symbol = pallet_token_non_fungible::Pallet::<Runtime>::token_symbol(id);
+		let symbol = collection.symbol();
 		// Build output.
 		Ok(succeed(EvmDataWriter::new().write::<Bytes>(symbol.as_slice().into()).build()))
 	}
 
 	fn token_uri(
 		id: NonFungibleTokenIdOf<Runtime>,
+		collection: &CollectionOf<Runtime>,
 		handle: &mut impl PrecompileHandle,
 	) -> EvmResult<PrecompileOutput> {
 		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
 		input.expect_arguments(1)?;
 
-		let token_id: Runtime::TokenId = input.read::<TokenId>()?.into();
+		let token_id = Self::read_token_id(&mut input)?;
+		if !pallet_token_non_fungible::Pallet::<Runtime>::token_exists(id, token_id) {
+			return Err(revert("token not found"))
+		}
 
 		let token_uri: Vec<u8> =
-			pallet_token_non_fungible::Pallet::<Runtime>::token_uri(id, token_id);
+			pallet_token_non_fungible::Pallet::<Runtime>::token_uri_in(collection, id, token_id);
 
 		Ok(succeed(EvmDataWriter::new().write::<Bytes>(token_uri.as_slice().into()).build()))
 	}
@@ -392,7 +778,11 @@ where
 		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
 		input.expect_arguments(1)?;
 
-		let token_index = input.read::<TokenIndex>()?.into();
+		let token_index = input.read::<TokenIndex>()?;
+		// Unset indices read as token 0, which is a valid id.
+		if token_index >= pallet_token_non_fungible::Pallet::<Runtime>::total_supply(id) {
+			return Err(revert("index out of bounds"))
+		}
 
 		let token_id: TokenId =
 			pallet_token_non_fungible::Pallet::<Runtime>::token_by_index(id, token_index).into();
@@ -410,7 +800,10 @@ where
 		let owner: Runtime::AccountId =
 			Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
 
-		let token_index = input.read::<TokenIndex>()?.into();
+		let token_index = input.read::<TokenIndex>()?;
+		if token_index >= pallet_token_non_fungible::Pallet::<Runtime>::balance_of(id, &owner) {
+			return Err(revert("index out of bounds"))
+		}
 
 		let token_id: TokenId =
 			pallet_token_non_fungible::Pallet::<Runtime>::token_of_owner_by_index(
@@ -422,4 +815,32 @@ where
 
 		Ok(succeed(EvmDataWriter::new().write(token_id).build()))
 	}
+	fn tokens_of_owner(
+		id: NonFungibleTokenIdOf<Runtime>,
+		handle: &mut impl PrecompileHandle,
+	) -> EvmResult<PrecompileOutput> {
+		let mut input = EvmDataReader::new_skip_selector(handle.input())?;
+		input.expect_arguments(3)?;
+
+		let owner: Runtime::AccountId =
+			Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+		let start = input.read::<TokenIndex>()?;
+		let limit = input.read::<TokenIndex>()?;
+
+		if limit > TOKENS_OF_OWNER_LIMIT {
+			return Err(revert("limit too large"))
+		}
+
+		handle.record_cost(
+			RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(limit as u64 + 1),
+		)?;
+
+		let token_ids: Vec<TokenId> =
+			pallet_token_non_fungible::Pallet::<Runtime>::tokens_of_owner(id, &owner, start, limit)
+				.into_iter()
+				.map(Into::into)
+				.collect();
+
+		Ok(succeed(EvmDataWriter::new().write(token_ids).build()))
+	}
 }
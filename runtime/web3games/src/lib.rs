@@ -80,7 +80,7 @@ pub use pallet_balances::Call as BalancesCall;
 use pallet_contracts::weights::WeightInfo;
 use pallet_ethereum::{Call::transact, Transaction as EthereumTransaction};
 use pallet_evm::{Account as EVMAccount, EnsureAddressTruncated, HashedAddressMapping, Runner};
-use pallet_support::AccountMapping;
+use pallet_support::{AccountMapping, TokenIdConversion};
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
@@ -95,6 +95,7 @@ pub use primitives::{
 };
 
 pub use chain_extensions::Web3GamesChainExtensions;
+use precompiles::FungibleTokenExtension;
 pub use precompiles::Web3GamesPrecompiles;
 pub type Precompiles = Web3GamesPrecompiles<Runtime>;
 
@@ -590,6 +591,12 @@ parameter_types! {
 	pub const ProxyPayPalletId: PalletId = PalletId(*b"w3g/prox");
 	pub ZeroAccountId: AccountId = AccountId::from([0u8; 32]);
 	pub const StringLimit: u32 = 50;
+	pub const MetadataOverflow: pallet_token_fungible::MetadataOverflowPolicy =
+		pallet_token_fungible::MetadataOverflowPolicy::Reject;
+	pub const TokenInactivityThreshold: BlockNumber = 180 * DAYS;
+	pub const MaxApprovalsPerOwner: Option<u32> = Some(100);
+	pub const FeeRounding: pallet_token_fungible::RoundingMode =
+		pallet_token_fungible::RoundingMode::Floor;
 }
 
 parameter_types! {
@@ -634,6 +641,26 @@ impl pallet_token_fungible::Config for Runtime {
 	type StringLimit = StringLimit;
 	type CreateTokenDeposit = CreateTokenDeposit;
 	type Currency = Balances;
+	type MaxBatchTransfers = ConstU32<100>;
+	type OnMetadataOverflow = MetadataOverflow;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type InactivityThreshold = TokenInactivityThreshold;
+	type NormalizeSymbols = ConstBool<false>;
+	type DefaultDecimals = ConstU8<18>;
+	type EmitBalanceChanged = ConstBool<false>;
+	type OnBalanceChanged = ();
+	type MintFee = ConstU128<0>;
+	type Randomness = RandomnessCollectiveFlip;
+	type MaxLotteryHolders = ConstU32<1000>;
+	type MaxBatchMints = ConstU32<100>;
+	type UnsafeRecipients = Nothing;
+	type MinNameLength = ConstU32<1>;
+	type MinSymbolLength = ConstU32<2>;
+	type MaxApprovalsPerOwner = MaxApprovalsPerOwner;
+	type MaxEmissions = ConstU32<20>;
+	type BridgeOrigin = EnsureRoot<AccountId>;
+	type MaxFeeSplits = ConstU32<8>;
+	type FeeRounding = FeeRounding;
 	type WeightInfo = pallet_token_fungible::weights::W3GWeight<Runtime>;
 }
 
@@ -846,8 +873,29 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations run on runtime upgrade.
+pub type Migrations = (
+	pallet_token_fungible::migrations::v1::MigrateToV1<Runtime>,
+	pallet_token_fungible::migrations::v2::MigrateToV2<Runtime>,
+	pallet_token_fungible::migrations::v3::MigrateToV3<Runtime>,
+	pallet_token_fungible::migrations::v4::MigrateToV4<Runtime>,
+	pallet_token_fungible::migrations::v5::MigrateToV5<Runtime>,
+	pallet_token_fungible::migrations::v6::MigrateToV6<Runtime>,
+	pallet_token_fungible::migrations::v7::MigrateToV7<Runtime>,
+	pallet_token_fungible::migrations::v8::MigrateToV8<Runtime>,
+	pallet_token_fungible::migrations::v9::MigrateToV9<Runtime>,
+	pallet_token_fungible::migrations::v10::MigrateToV10<Runtime>,
+	pallet_token_fungible::migrations::v11::MigrateToV11<Runtime>,
+	pallet_token_fungible::migrations::v12::MigrateToV12<Runtime>,
+	pallet_token_fungible::migrations::v13::MigrateToV13<Runtime>,
+	pallet_token_fungible::migrations::v14::MigrateToV14<Runtime>,
+	pallet_token_fungible::migrations::v15::MigrateToV15<Runtime>,
+	pallet_token_fungible::migrations::v16::MigrateToV16<Runtime>,
+);
+
 impl fp_self_contained::SelfContainedCall for Call {
 	type SignedInfo = H160;
 
@@ -1301,6 +1349,87 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_token_fungible_rpc_runtime_api::TokenFungibleRuntimeApi<Block, TokenAssetId, AccountId, BlockNumber> for Runtime {
+		fn all_tokens(
+			start: Option<TokenAssetId>,
+			limit: u32,
+		) -> Vec<(TokenAssetId, pallet_token_fungible::TokenInfo<AccountId, BlockNumber>)> {
+			TokenFungible::all_tokens(start, limit)
+		}
+
+		fn can_transfer_from(
+			id: TokenAssetId,
+			sender: AccountId,
+			spender: AccountId,
+			recipient: AccountId,
+			amount: Balance,
+		) -> Result<(), sp_runtime::DispatchError> {
+			TokenFungible::validate_transfer_from(id, &spender, &sender, &recipient, amount)
+		}
+
+		fn formatted_balance(id: TokenAssetId, who: AccountId) -> Vec<u8> {
+			TokenFungible::formatted_balance(id, &who)
+		}
+
+		fn spendable_balance(id: TokenAssetId, who: AccountId) -> Balance {
+			TokenFungible::spendable_balance(id, &who)
+		}
+
+		fn allowance_batch(
+			id: TokenAssetId,
+			owner: AccountId,
+			spenders: Vec<AccountId>,
+		) -> Vec<Balance> {
+			TokenFungible::allowance_batch(id, &owner, spenders)
+		}
+
+		fn approvals_count(id: TokenAssetId, owner: AccountId) -> u32 {
+			TokenFungible::approvals_count(id, owner)
+		}
+
+		fn total_approved(id: TokenAssetId, owner: AccountId) -> Balance {
+			TokenFungible::total_approved(id, &owner)
+		}
+
+		fn validate_create(
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> Result<(), sp_runtime::DispatchError> {
+			TokenFungible::validate_create(name, symbol, decimals)
+		}
+
+		fn peek_token_ids(count: u32) -> Vec<TokenAssetId> {
+			TokenFungible::peek_token_ids(count)
+		}
+
+		fn balance_of(id: TokenAssetId, who: AccountId) -> Balance {
+			TokenFungible::balance_of(id, who)
+		}
+
+		fn allowance(id: TokenAssetId, owner: AccountId, spender: AccountId) -> Balance {
+			TokenFungible::allowances(id, (owner, spender))
+		}
+
+		fn total_supply(id: TokenAssetId) -> Balance {
+			TokenFungible::total_supply(id)
+		}
+
+		fn token_info(
+			id: TokenAssetId,
+		) -> Option<pallet_token_fungible::TokenInfo<AccountId, BlockNumber>> {
+			TokenFungible::token_info(id)
+		}
+
+		fn evm_address_of(id: TokenAssetId) -> H160 {
+			FungibleTokenExtension::<Runtime>::into_address(id)
+		}
+
+		fn token_id_from_evm_address(address: H160) -> Option<TokenAssetId> {
+			FungibleTokenExtension::<Runtime>::try_from_address(address)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (